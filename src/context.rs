@@ -21,6 +21,10 @@
 //! slot.set_root_hub_port_number(port_id);
 //! ```
 
+use crate::{
+    error, ids::EndpointId, registers::capability::CapabilityParameters1,
+    ring::trb::transfer::Direction, Error,
+};
 use bit_field::BitField;
 use core::convert::TryInto;
 use num_derive::FromPrimitive;
@@ -282,6 +286,158 @@ macro_rules! cx {
 cx!(32);
 cx!(64);
 
+/// The size of the Contexts which the xHC uses.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use core::num::NonZeroUsize;
+/// # use xhci::accessor::Mapper;
+/// #
+/// # const MMIO_BASE: usize = 0x1000;
+/// #
+/// # #[derive(Clone)]
+/// # struct MemoryMapper;
+/// # impl Mapper for MemoryMapper {
+/// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+/// #         unimplemented!()
+/// #     }
+/// #
+/// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+/// #         unimplemented!()
+/// #     }
+/// # }
+/// #
+/// # let mapper = MemoryMapper;
+/// use xhci::context::{Input, Size};
+///
+/// let r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+/// let size = Size::new(r.capability.hccparams1.read());
+///
+/// let input = Input::new(size);
+/// ```
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Size {
+    /// 32-byte Contexts.
+    Byte32,
+    /// 64-byte Contexts.
+    Byte64,
+}
+impl Size {
+    /// Returns the size of the Contexts specified by the Context Size bit.
+    #[must_use]
+    pub fn new(hccparams1: CapabilityParameters1) -> Self {
+        if hccparams1.context_size() {
+            Self::Byte64
+        } else {
+            Self::Byte32
+        }
+    }
+}
+
+/// Input Context of either size.
+///
+/// Note that the wrapped Context does not start at the address of this enum. Match the variants to
+/// get the address of the Context passed to the xHC.
+// The variants cannot be boxed because this crate does not use `alloc`.
+#[allow(clippy::large_enum_variant)]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Input {
+    /// 32-byte Input Context.
+    Byte32(byte32::Input),
+    /// 64-byte Input Context.
+    Byte64(byte64::Input),
+}
+impl Input {
+    /// Creates a null Input Context of the size `s`.
+    #[must_use]
+    pub const fn new(s: Size) -> Self {
+        match s {
+            Size::Byte32 => Self::Byte32(byte32::Input::new()),
+            Size::Byte64 => Self::Byte64(byte64::Input::new()),
+        }
+    }
+
+    /// Returns the size of the Context.
+    #[must_use]
+    pub fn size(&self) -> Size {
+        match self {
+            Self::Byte32(_) => Size::Byte32,
+            Self::Byte64(_) => Size::Byte64,
+        }
+    }
+}
+impl InputHandler for Input {
+    fn control_mut(&mut self) -> &mut dyn InputControlHandler {
+        match self {
+            Self::Byte32(i) => i.control_mut(),
+            Self::Byte64(i) => i.control_mut(),
+        }
+    }
+
+    fn device_mut(&mut self) -> &mut dyn DeviceHandler {
+        match self {
+            Self::Byte32(i) => i.device_mut(),
+            Self::Byte64(i) => i.device_mut(),
+        }
+    }
+}
+
+/// Device Context of either size.
+///
+/// Note that the wrapped Context does not start at the address of this enum. Match the variants to
+/// get the address of the Context passed to the xHC.
+// The variants cannot be boxed because this crate does not use `alloc`.
+#[allow(clippy::large_enum_variant)]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Device {
+    /// 32-byte Device Context.
+    Byte32(byte32::Device),
+    /// 64-byte Device Context.
+    Byte64(byte64::Device),
+}
+impl Device {
+    /// Creates a null Device Context of the size `s`.
+    #[must_use]
+    pub const fn new(s: Size) -> Self {
+        match s {
+            Size::Byte32 => Self::Byte32(byte32::Device::new()),
+            Size::Byte64 => Self::Byte64(byte64::Device::new()),
+        }
+    }
+
+    /// Returns the size of the Context.
+    #[must_use]
+    pub fn size(&self) -> Size {
+        match self {
+            Self::Byte32(_) => Size::Byte32,
+            Self::Byte64(_) => Size::Byte64,
+        }
+    }
+}
+impl DeviceHandler for Device {
+    fn slot_mut(&mut self) -> &mut dyn SlotHandler {
+        match self {
+            Self::Byte32(d) => d.slot_mut(),
+            Self::Byte64(d) => d.slot_mut(),
+        }
+    }
+
+    fn endpoint0_mut(&mut self) -> &mut dyn EndpointHandler {
+        match self {
+            Self::Byte32(d) => d.endpoint0_mut(),
+            Self::Byte64(d) => d.endpoint0_mut(),
+        }
+    }
+
+    fn endpoints_mut(&mut self, i: usize) -> &mut dyn EndpointPairHandler {
+        match self {
+            Self::Byte32(d) => d.endpoints_mut(i),
+            Self::Byte64(d) => d.endpoints_mut(i),
+        }
+    }
+}
+
 /// A trait to handle the Input Context.
 ///
 /// # Examples
@@ -380,6 +536,30 @@ pub trait DeviceHandler {
     /// let ep0 = device.endpoints_mut(0);
     /// ```
     fn endpoints_mut(&mut self, i: usize) -> &mut dyn EndpointPairHandler;
+
+    /// Returns a mutable reference to the Endpoint Context of the endpoint `id`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::context::{byte32::Device, DeviceHandler};
+    /// use xhci::ids::EndpointId;
+    /// use xhci::ring::trb::transfer::Direction;
+    ///
+    /// let mut device = Device::new();
+    /// let ep1_in = EndpointId::from_endpoint(1, Direction::In).unwrap();
+    ///
+    /// device.endpoint_mut(ep1_in).set_mult(0);
+    /// ```
+    fn endpoint_mut(&mut self, id: EndpointId) -> &mut dyn EndpointHandler {
+        let i = id.endpoint_number().into();
+
+        match id.direction() {
+            None => self.endpoint0_mut(),
+            Some(Direction::Out) => self.endpoints_mut(i).output_mut(),
+            Some(Direction::In) => self.endpoints_mut(i).input_mut(),
+        }
+    }
 }
 
 /// A trait to handle a pair of the Endpoint Context.
@@ -435,7 +615,7 @@ pub trait EndpointPairHandler {
 /// slot.set_context_entries(1);
 /// slot.set_root_hub_port_number(port_number);
 /// ```
-pub trait SlotHandler: AsMut<[u32]> {
+pub trait SlotHandler: AsRef<[u32]> + AsMut<[u32]> {
     /// Sets the value of the Context Entries field.
     ///
     /// # Examples
@@ -468,6 +648,47 @@ pub trait SlotHandler: AsMut<[u32]> {
     fn set_root_hub_port_number(&mut self, n: u8) {
         self.as_mut()[1].set_bits(16..=23, n.into());
     }
+
+    /// Clears the reserved fields.
+    ///
+    /// The xHC may write arbitrary values to the reserved fields. Call this method on a Context
+    /// read back from the xHC before inspecting or reusing it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::context::{byte32::Device, DeviceHandler};
+    ///
+    /// let mut device = Device::new();
+    /// let slot = device.slot_mut();
+    ///
+    /// slot.clear_reserved_fields();
+    /// ```
+    fn clear_reserved_fields(&mut self) {
+        clear_reserved_fields(self.as_mut(), &SLOT_MASKS);
+    }
+
+    /// Checks that all the reserved fields are zero.
+    ///
+    /// Call this method before passing the Context to the xHC.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if any of the reserved fields is not zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::context::{byte32::Device, DeviceHandler};
+    ///
+    /// let mut device = Device::new();
+    /// let slot = device.slot_mut();
+    ///
+    /// assert!(slot.validate_reserved_fields().is_ok());
+    /// ```
+    fn validate_reserved_fields(&self) -> Result<(), NonZeroReservedField> {
+        validate_reserved_fields(self.as_ref(), &SLOT_MASKS)
+    }
 }
 
 /// A trait to handle the Input Control Context.
@@ -485,7 +706,7 @@ pub trait SlotHandler: AsMut<[u32]> {
 ///
 /// control.clear_aflag(1);
 /// ```
-pub trait InputControlHandler: AsMut<[u32]> {
+pub trait InputControlHandler: AsRef<[u32]> + AsMut<[u32]> {
     /// Sets the `i`th Add Context flag.
     ///
     /// # Panics
@@ -528,6 +749,81 @@ pub trait InputControlHandler: AsMut<[u32]> {
         assert!(i < 32, "There exists only 0..=31 Add Context flags.");
         self.as_mut()[1].set_bit(i, false);
     }
+
+    /// Sets the Add Context flag of the endpoint `id`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::context::{byte32::Input, InputHandler};
+    /// use xhci::ids::EndpointId;
+    ///
+    /// let mut input = Input::new();
+    /// let control = input.control_mut();
+    ///
+    /// control.set_aflag_of(EndpointId::DEFAULT_CONTROL);
+    /// ```
+    fn set_aflag_of(&mut self, id: EndpointId) {
+        self.set_aflag(id.into());
+    }
+
+    /// Clears the Add Context flag of the endpoint `id`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::context::{byte32::Input, InputHandler};
+    /// use xhci::ids::EndpointId;
+    ///
+    /// let mut input = Input::new();
+    /// let control = input.control_mut();
+    ///
+    /// control.clear_aflag_of(EndpointId::DEFAULT_CONTROL);
+    /// ```
+    fn clear_aflag_of(&mut self, id: EndpointId) {
+        self.clear_aflag(id.into());
+    }
+
+    /// Clears the reserved fields.
+    ///
+    /// The xHC may write arbitrary values to the reserved fields. Call this method on a Context
+    /// read back from the xHC before inspecting or reusing it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::context::{byte32::Input, InputHandler};
+    ///
+    /// let mut input = Input::new();
+    /// let control = input.control_mut();
+    ///
+    /// control.clear_reserved_fields();
+    /// ```
+    fn clear_reserved_fields(&mut self) {
+        clear_reserved_fields(self.as_mut(), &INPUT_CONTROL_MASKS);
+    }
+
+    /// Checks that all the reserved fields are zero.
+    ///
+    /// Call this method before passing the Context to the xHC.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if any of the reserved fields is not zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::context::{byte32::Input, InputHandler};
+    ///
+    /// let mut input = Input::new();
+    /// let control = input.control_mut();
+    ///
+    /// assert!(control.validate_reserved_fields().is_ok());
+    /// ```
+    fn validate_reserved_fields(&self) -> Result<(), NonZeroReservedField> {
+        validate_reserved_fields(self.as_ref(), &INPUT_CONTROL_MASKS)
+    }
 }
 
 /// A trait to handle the Endpoint Context.
@@ -542,7 +838,7 @@ pub trait InputControlHandler: AsMut<[u32]> {
 ///
 /// ep0.set_mult(0);
 /// ```
-pub trait EndpointHandler: AsMut<[u32]> {
+pub trait EndpointHandler: AsRef<[u32]> + AsMut<[u32]> {
     /// Sets the value of the Mult field.
     ///
     /// # Panics
@@ -691,7 +987,8 @@ pub trait EndpointHandler: AsMut<[u32]> {
     ///
     /// # Panics
     ///
-    /// This method panics if `p` is not 16 byte aligned.
+    /// This method panics if `p` is not 16 byte aligned. Use
+    /// [`EndpointHandler::try_set_transfer_ring_dequeue_pointer`] to handle the error.
     ///
     /// # Examples
     ///
@@ -713,7 +1010,369 @@ pub trait EndpointHandler: AsMut<[u32]> {
         self.as_mut()[2] = l | self.as_mut()[2].get_bit(0) as u32;
         self.as_mut()[3] = u;
     }
+
+    /// Sets the value of the Transfer Ring Dequeue pointer field without panicking.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::MisalignedPointer`] if `p` is not 16 byte aligned. The field
+    /// is not changed in this case.
+    fn try_set_transfer_ring_dequeue_pointer(&mut self, p: u64) -> Result<(), Error> {
+        error::check_alignment(p, 16)?;
+        self.set_transfer_ring_dequeue_pointer(p);
+        Ok(())
+    }
+
+    /// Clears the reserved fields.
+    ///
+    /// The xHC may write arbitrary values to the reserved fields. Call this method on a Context
+    /// read back from the xHC before inspecting or reusing it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::context::{byte32::Device, DeviceHandler};
+    ///
+    /// let mut device = Device::new();
+    /// let ep0 = device.endpoint0_mut();
+    ///
+    /// ep0.clear_reserved_fields();
+    /// ```
+    fn clear_reserved_fields(&mut self) {
+        clear_reserved_fields(self.as_mut(), &ENDPOINT_MASKS);
+    }
+
+    /// Checks that all the reserved fields are zero.
+    ///
+    /// Call this method before passing the Context to the xHC.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if any of the reserved fields is not zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::context::{byte32::Device, DeviceHandler};
+    ///
+    /// let mut device = Device::new();
+    /// let ep0 = device.endpoint0_mut();
+    ///
+    /// assert!(ep0.validate_reserved_fields().is_ok());
+    /// ```
+    fn validate_reserved_fields(&self) -> Result<(), NonZeroReservedField> {
+        validate_reserved_fields(self.as_ref(), &ENDPOINT_MASKS)
+    }
+}
+
+/// The error returned when a reserved field of a Context is not zero.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct NonZeroReservedField {
+    /// The index of the dword containing the field.
+    pub dword: usize,
+    /// The non-zero reserved bits of the dword.
+    pub bits: u32,
+}
+
+// The masks of the non-reserved bits of each dword. The dwords which do not appear in an array,
+// such as the last 32 bytes of the 64-byte Contexts, are reserved entirely.
+const SLOT_MASKS: [u32; 4] = [!(1 << 24), 0xffff_ffff, !(0b1111 << 18), !(0x7ffff << 8)];
+const INPUT_CONTROL_MASKS: [u32; 8] = [!0b11, 0xffff_ffff, 0, 0, 0, 0, 0, 0x00ff_ffff];
+const ENDPOINT_MASKS: [u32; 5] = [
+    !(0b1_1111 << 3),
+    !(1 << 6 | 1),
+    !(0b111 << 1),
+    0xffff_ffff,
+    0xffff_ffff,
+];
+
+fn clear_reserved_fields(c: &mut [u32], masks: &[u32]) {
+    for (i, d) in c.iter_mut().enumerate() {
+        *d &= masks.get(i).copied().unwrap_or(0);
+    }
+}
+
+fn validate_reserved_fields(c: &[u32], masks: &[u32]) -> Result<(), NonZeroReservedField> {
+    for (dword, d) in c.iter().enumerate() {
+        let bits = d & !masks.get(dword).copied().unwrap_or(0);
+
+        if bits != 0 {
+            return Err(NonZeroReservedField { dword, bits });
+        }
+    }
+
+    Ok(())
+}
+
+/// Port Bandwidth Context.
+///
+/// The xHC writes the percentage of the available bandwidth of each Root Hub Port to this
+/// structure on the completion of a Get Port Bandwidth Command.
+///
+/// # Examples
+///
+/// ```
+/// use xhci::context::PortBandwidth;
+///
+/// let b = PortBandwidth::new();
+/// # let port_number = 1;
+/// let percentage = b.available_bandwidth(port_number);
+/// ```
+#[repr(C, align(16))]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct PortBandwidth([u8; 256]);
+impl PortBandwidth {
+    /// Creates a null Port Bandwidth Context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::context::PortBandwidth;
+    ///
+    /// let b = PortBandwidth::new();
+    /// ```
+    #[must_use]
+    pub const fn new() -> Self {
+        Self([0; 256])
+    }
+
+    /// Returns the percentage of the available bandwidth of the Root Hub Port `port_number`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `port_number == 0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::context::PortBandwidth;
+    ///
+    /// let b = PortBandwidth::new();
+    /// assert_eq!(b.available_bandwidth(1), 0);
+    /// ```
+    #[must_use]
+    pub fn available_bandwidth(&self, port_number: u8) -> u8 {
+        assert_ne!(port_number, 0, "The Root Hub Port Number starts from 1.");
+
+        self.0[usize::from(port_number)]
+    }
+}
+impl Default for PortBandwidth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl AsRef<[u8]> for PortBandwidth {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Debug Capability Context.
+///
+/// This structure consists of the DbC Info Context and the Endpoint Contexts of the OUT and the
+/// IN Bulk Endpoints. Each of them is 64 bytes regardless of the Context Size bit. Write the
+/// address of this structure to the Debug Capability Context Pointer Register.
+///
+/// # Examples
+///
+/// ```
+/// use xhci::context::{DebugCapability, EndpointType};
+///
+/// let mut c = DebugCapability::new();
+/// # let string0 = 0x1000;
+/// # let string0_len = 4;
+/// c.info_mut().set_string0_descriptor_address(string0);
+/// c.info_mut().set_string0_length(string0_len);
+///
+/// c.endpoint_out_mut().set_endpoint_type(EndpointType::BulkOut);
+/// c.endpoint_in_mut().set_endpoint_type(EndpointType::BulkIn);
+/// ```
+#[repr(C, align(16))]
+#[derive(Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct DebugCapability {
+    info: DebugCapabilityInfo,
+    endpoint_out: DebugEndpoint,
+    endpoint_in: DebugEndpoint,
+}
+impl DebugCapability {
+    /// Creates a null Debug Capability Context.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            info: DebugCapabilityInfo::new(),
+            endpoint_out: DebugEndpoint([0; 16]),
+            endpoint_in: DebugEndpoint([0; 16]),
+        }
+    }
+
+    /// Returns a mutable reference to the DbC Info Context.
+    pub fn info_mut(&mut self) -> &mut DebugCapabilityInfo {
+        &mut self.info
+    }
+
+    /// Returns a mutable reference to the Endpoint Context of the OUT Bulk Endpoint.
+    pub fn endpoint_out_mut(&mut self) -> &mut dyn EndpointHandler {
+        &mut self.endpoint_out
+    }
+
+    /// Returns a mutable reference to the Endpoint Context of the IN Bulk Endpoint.
+    pub fn endpoint_in_mut(&mut self) -> &mut dyn EndpointHandler {
+        &mut self.endpoint_in
+    }
+}
+
+/// DbC Info Context.
+///
+/// The addresses of the string descriptors must be 16 byte aligned.
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct DebugCapabilityInfo([u32; 16]);
+impl DebugCapabilityInfo {
+    /// Creates a null DbC Info Context.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self([0; 16])
+    }
+
+    /// Returns the value of the String 0 Descriptor Address field.
+    #[must_use]
+    pub fn string0_descriptor_address(&self) -> u64 {
+        self.address(0)
+    }
+
+    /// Sets the value of the String 0 Descriptor Address field.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `a` is not 16 byte aligned.
+    pub fn set_string0_descriptor_address(&mut self, a: u64) {
+        self.set_address(0, a);
+    }
+
+    /// Returns the value of the Manufacturer String Descriptor Address field.
+    #[must_use]
+    pub fn manufacturer_string_descriptor_address(&self) -> u64 {
+        self.address(2)
+    }
+
+    /// Sets the value of the Manufacturer String Descriptor Address field.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `a` is not 16 byte aligned.
+    pub fn set_manufacturer_string_descriptor_address(&mut self, a: u64) {
+        self.set_address(2, a);
+    }
+
+    /// Returns the value of the Product String Descriptor Address field.
+    #[must_use]
+    pub fn product_string_descriptor_address(&self) -> u64 {
+        self.address(4)
+    }
+
+    /// Sets the value of the Product String Descriptor Address field.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `a` is not 16 byte aligned.
+    pub fn set_product_string_descriptor_address(&mut self, a: u64) {
+        self.set_address(4, a);
+    }
+
+    /// Returns the value of the Serial Number String Descriptor Address field.
+    #[must_use]
+    pub fn serial_number_string_descriptor_address(&self) -> u64 {
+        self.address(6)
+    }
+
+    /// Sets the value of the Serial Number String Descriptor Address field.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `a` is not 16 byte aligned.
+    pub fn set_serial_number_string_descriptor_address(&mut self, a: u64) {
+        self.set_address(6, a);
+    }
+
+    /// Returns the value of the String 0 Length field.
+    #[must_use]
+    pub fn string0_length(&self) -> u8 {
+        self.0[8].get_bits(0..=7).try_into().unwrap()
+    }
+
+    /// Sets the value of the String 0 Length field.
+    pub fn set_string0_length(&mut self, l: u8) {
+        self.0[8].set_bits(0..=7, l.into());
+    }
+
+    /// Returns the value of the Manufacturer String Length field.
+    #[must_use]
+    pub fn manufacturer_string_length(&self) -> u8 {
+        self.0[8].get_bits(8..=15).try_into().unwrap()
+    }
+
+    /// Sets the value of the Manufacturer String Length field.
+    pub fn set_manufacturer_string_length(&mut self, l: u8) {
+        self.0[8].set_bits(8..=15, l.into());
+    }
+
+    /// Returns the value of the Product String Length field.
+    #[must_use]
+    pub fn product_string_length(&self) -> u8 {
+        self.0[8].get_bits(16..=23).try_into().unwrap()
+    }
+
+    /// Sets the value of the Product String Length field.
+    pub fn set_product_string_length(&mut self, l: u8) {
+        self.0[8].set_bits(16..=23, l.into());
+    }
+
+    /// Returns the value of the Serial Number String Length field.
+    #[must_use]
+    pub fn serial_number_string_length(&self) -> u8 {
+        self.0[8].get_bits(24..=31).try_into().unwrap()
+    }
+
+    /// Sets the value of the Serial Number String Length field.
+    pub fn set_serial_number_string_length(&mut self, l: u8) {
+        self.0[8].set_bits(24..=31, l.into());
+    }
+
+    fn address(&self, i: usize) -> u64 {
+        u64::from(self.0[i]) | u64::from(self.0[i + 1]) << 32
+    }
+
+    fn set_address(&mut self, i: usize, a: u64) {
+        assert!(
+            a.trailing_zeros() >= 4,
+            "The address of a string descriptor must be 16 byte aligned."
+        );
+
+        self.0[i] = a.get_bits(0..32).try_into().unwrap();
+        self.0[i + 1] = a.get_bits(32..64).try_into().unwrap();
+    }
+}
+impl AsRef<[u32]> for DebugCapabilityInfo {
+    fn as_ref(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+struct DebugEndpoint([u32; 16]);
+impl AsRef<[u32]> for DebugEndpoint {
+    fn as_ref(&self) -> &[u32] {
+        &self.0
+    }
+}
+impl AsMut<[u32]> for DebugEndpoint {
+    fn as_mut(&mut self) -> &mut [u32] {
+        &mut self.0
+    }
 }
+impl EndpointHandler for DebugEndpoint {}
 
 /// Endpoint Type.
 ///
@@ -0,0 +1,408 @@
+//! An in-memory emulator of an xHC.
+//!
+//! This module is available with the `emulator` feature. It allows testing the initialization
+//! sequence of a driver without hardware.
+//!
+//! The registers of the emulated xHC live in an ordinary memory region, [`Mmio`]. The driver
+//! accesses them through [`IdentityMapper`], and all the addresses written to the registers, such
+//! as the Command Ring Pointer, are treated as the addresses of the memory of the current process.
+//!
+//! The emulator does not run by itself. Call [`Controller::step`] to make it react to the values
+//! written to the registers, for example, in the `delay` closures of the polling methods. Each
+//! call performs the following:
+//!
+//! - While the Controller Not Ready bit is set, the call only counts down. The bit is cleared by
+//!   the [`Controller::READY_DELAY_STEPS`]th call after the power-on or a reset.
+//! - If the Host Controller Reset bit is set, the Operational and the Runtime Registers are reset,
+//!   the bit is cleared, and the Controller Not Ready bit is set.
+//! - The Host Controller Halted bit follows the Run/Stop bit.
+//! - The values written to the Command Ring Control Register are latched, and the register is
+//!   cleared as the Command Ring Pointer reads as zero.
+//! - The Event Ring of the primary interrupter is latched when the xHC starts running. Only the
+//!   first segment is used.
+//! - If the Host Controller Command doorbell was rung while the xHC is running, each command on
+//!   the Command Ring is completed successfully and a Command Completion Event is placed on the
+//!   Event Ring. An Enable Slot Command allocates the Slot IDs from 1.
+//!
+//! Interrupts, ports, and the doorbells of the Device Slots are not emulated. The
+//! write-1-to-clear bits keep the written values.
+//!
+//! # Examples
+//!
+//! ```
+//! use xhci::{
+//!     emulator::{Controller, IdentityMapper, Mmio},
+//!     registers::runtime::InterrupterConfiguration,
+//!     ring::trb::{command::EnableSlot, event},
+//! };
+//! use core::convert::TryFrom;
+//!
+//! #[repr(C, align(64))]
+//! struct Aligned<T>(T);
+//!
+//! let mut mmio = Mmio::new();
+//! let mut xhc = Controller::new(&mut mmio);
+//! let mut r = unsafe { xhci::Registers::new(xhc.mmio_base(), IdentityMapper) };
+//! let mut step = || {
+//!     unsafe { xhc.step() };
+//!     true
+//! };
+//!
+//! r.operational.wait_until_ready(&mut step).unwrap();
+//!
+//! let command_ring = Aligned([EnableSlot::new().set_cycle_bit(true).into_raw(), [0; 4]]);
+//! let event_ring = Aligned([[0_u32; 4]; 16]);
+//! let event_ring_addr = event_ring.0.as_ptr() as u64;
+//! let erst = Aligned([event_ring_addr as u32, (event_ring_addr >> 32) as u32, 16, 0]);
+//!
+//! r.try_set_command_ring(command_ring.0.as_ptr() as u64, true)
+//!     .unwrap();
+//! r.interrupt_register_set
+//!     .interrupter(0)
+//!     .configure(&InterrupterConfiguration {
+//!         erst_base: erst.0.as_ptr() as u64,
+//!         erst_size: 1,
+//!         dequeue_pointer: event_ring_addr,
+//!         moderation_interval: 0,
+//!         interrupt_enable: false,
+//!     });
+//! r.operational.usbcmd.update(|c| c.set_run_stop(true));
+//! step();
+//!
+//! r.ring_command_doorbell();
+//! step();
+//!
+//! let e = unsafe { core::ptr::read_volatile(&event_ring.0[0]) };
+//! match event::Allowed::try_from(e) {
+//!     Ok(event::Allowed::CommandCompletion(c)) => assert_eq!(c.slot_id(), 1),
+//!     _ => unreachable!(),
+//! }
+//! ```
+
+use crate::ring::trb::{event::CompletionCode, Type};
+use accessor::Mapper;
+use bit_field::BitField;
+use core::{
+    convert::{TryFrom, TryInto},
+    fmt,
+    marker::PhantomData,
+    num::NonZeroUsize,
+    ptr,
+};
+
+/// The length of the MMIO space of the emulated xHC in bytes.
+pub const MMIO_LEN: usize = 0x3000;
+
+const CAPLENGTH: usize = 0x20;
+const HCIVERSION: u32 = 0x0110;
+const MAX_SLOTS: u8 = 8;
+const MAX_INTRS: u32 = 1;
+const MAX_PORTS: u32 = 4;
+const RTSOFF: usize = 0x1000;
+const DBOFF: usize = 0x2000;
+
+const USBCMD: usize = CAPLENGTH;
+const USBSTS: usize = CAPLENGTH + 0x04;
+const PAGESIZE: usize = CAPLENGTH + 0x08;
+const CRCR: usize = CAPLENGTH + 0x18;
+const PORTSC: usize = CAPLENGTH + 0x400;
+const INTERRUPTER: usize = RTSOFF + 0x20;
+const ERSTSZ: usize = INTERRUPTER + 0x08;
+const ERSTBA: usize = INTERRUPTER + 0x10;
+
+// The Host Controller Command doorbell reads as this value until it is written. Writing 0 to it
+// cannot be detected otherwise.
+const DOORBELL_IDLE: u32 = 0xffff_ffff;
+
+// Stops processing the commands in case the Command Ring is a loop of Link TRBs.
+const MAX_TRBS_PER_DOORBELL: usize = 1024;
+
+/// The memory region holding the registers of the emulated xHC.
+#[repr(C, align(4096))]
+#[derive(Copy, Clone)]
+pub struct Mmio([u32; MMIO_LEN / 4]);
+impl Mmio {
+    /// Creates a zero-filled region. [`Controller::new`] initializes the registers.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self([0; MMIO_LEN / 4])
+    }
+}
+impl Default for Mmio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl fmt::Debug for Mmio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mmio").finish_non_exhaustive()
+    }
+}
+
+/// A [`Mapper`] which maps each physical address to the same virtual address.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct IdentityMapper;
+impl Mapper for IdentityMapper {
+    unsafe fn map(&mut self, phys_start: usize, _bytes: usize) -> NonZeroUsize {
+        NonZeroUsize::new(phys_start).expect("Cannot map the null address.")
+    }
+
+    fn unmap(&mut self, _virt_start: usize, _bytes: usize) {}
+}
+
+/// The emulated xHC.
+///
+/// See [the module-level documentation](self) for the emulated behavior.
+#[derive(Debug)]
+pub struct Controller<'a> {
+    base: *mut u32,
+    not_ready_steps: u32,
+    command_ring: Option<Cursor>,
+    event_ring: Option<EventRing>,
+    next_slot_id: u8,
+    _marker: PhantomData<&'a mut Mmio>,
+}
+impl<'a> Controller<'a> {
+    /// The number of the calls of [`Controller::step`] until the Controller Not Ready bit is
+    /// cleared.
+    pub const READY_DELAY_STEPS: u32 = 3;
+
+    /// Creates an emulated xHC in the power-on state, using `mmio` as its MMIO space.
+    ///
+    /// The xHC has 8 Device Slots, 1 interrupter, 4 ports, and supports 64-bit addressing. It has
+    /// no Extended Capabilities.
+    pub fn new(mmio: &'a mut Mmio) -> Self {
+        let mut c = Self {
+            base: mmio.0.as_mut_ptr(),
+            not_ready_steps: 0,
+            command_ring: None,
+            event_ring: None,
+            next_slot_id: 1,
+            _marker: PhantomData,
+        };
+
+        c.write(0x00, u32::try_from(CAPLENGTH).unwrap() | HCIVERSION << 16);
+        c.write(
+            0x04,
+            u32::from(MAX_SLOTS) | MAX_INTRS << 8 | MAX_PORTS << 24,
+        );
+        c.write(0x10, 1);
+        c.write(0x14, u32::try_from(DBOFF).unwrap());
+        c.write(0x18, u32::try_from(RTSOFF).unwrap());
+        c.reset();
+
+        c
+    }
+
+    /// Returns the address of the MMIO space, which is passed to [`crate::Registers::new`] with
+    /// [`IdentityMapper`].
+    #[must_use]
+    pub fn mmio_base(&self) -> usize {
+        self.base as usize
+    }
+
+    /// Reacts to the values written to the registers.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the Command Ring Pointer, the Event Ring Segment Table Base
+    /// Address, and the addresses in the rings point to valid memory which is not accessed
+    /// during this call.
+    pub unsafe fn step(&mut self) {
+        if self.not_ready_steps > 0 {
+            self.not_ready_steps -= 1;
+            if self.not_ready_steps == 0 {
+                self.update(USBSTS, |s| {
+                    s.set_bit(11, false);
+                });
+            }
+        } else if self.read(USBCMD).get_bit(1) {
+            self.reset();
+        } else {
+            self.update_run_state();
+            self.latch_command_ring();
+            self.handle_command_doorbell();
+        }
+    }
+
+    fn reset(&mut self) {
+        for offset in (USBCMD..PORTSC).step_by(4) {
+            self.write(offset, 0);
+        }
+        for offset in (INTERRUPTER..INTERRUPTER + 0x20).step_by(4) {
+            self.write(offset, 0);
+        }
+
+        self.write(USBSTS, 1 << 0 | 1 << 11);
+        self.write(PAGESIZE, 1);
+        for port in 0..usize::try_from(MAX_PORTS).unwrap() {
+            self.write(PORTSC + port * 0x10, 1 << 9);
+        }
+        self.write(DBOFF, DOORBELL_IDLE);
+
+        self.not_ready_steps = Self::READY_DELAY_STEPS;
+        self.command_ring = None;
+        self.event_ring = None;
+        self.next_slot_id = 1;
+    }
+
+    unsafe fn update_run_state(&mut self) {
+        let running = self.read(USBCMD).get_bit(0);
+
+        if running && self.event_ring.is_none() && self.read(ERSTSZ) > 0 {
+            let erstba = self.read_u64(ERSTBA) & !0x3f;
+            let entry = read_trb(erstba);
+            let base = u64::from(entry[0]) | u64::from(entry[1]) << 32;
+
+            self.event_ring = Some(EventRing {
+                base: base & !0x3f,
+                len: entry[2].get_bits(0..16).into(),
+                cursor: Cursor {
+                    pointer: base & !0x3f,
+                    cycle: true,
+                },
+            });
+        }
+
+        self.update(USBSTS, |s| {
+            s.set_bit(0, !running);
+        });
+    }
+
+    fn latch_command_ring(&mut self) {
+        let crcr = self.read_u64(CRCR);
+        let pointer = crcr & !0x3f;
+
+        if pointer != 0 {
+            self.command_ring = Some(Cursor {
+                pointer,
+                cycle: crcr.get_bit(0),
+            });
+            self.write(CRCR, 0);
+            self.write(CRCR + 4, 0);
+        }
+    }
+
+    unsafe fn handle_command_doorbell(&mut self) {
+        if self.read(DBOFF) == DOORBELL_IDLE {
+            return;
+        }
+        self.write(DBOFF, DOORBELL_IDLE);
+
+        if self.read(USBSTS).get_bit(0) {
+            return;
+        }
+
+        for _ in 0..MAX_TRBS_PER_DOORBELL {
+            if !self.process_command() {
+                break;
+            }
+        }
+    }
+
+    // Returns `false` if there is no command to process.
+    unsafe fn process_command(&mut self) -> bool {
+        let Some(ring) = &mut self.command_ring else {
+            return false;
+        };
+        let pointer = ring.pointer;
+        let trb = read_trb(pointer);
+
+        if trb[3].get_bit(0) != ring.cycle {
+            return false;
+        }
+
+        if trb[3].get_bits(10..=15) == Type::Link as u32 {
+            ring.pointer = (u64::from(trb[0]) | u64::from(trb[1]) << 32) & !0xf;
+            ring.cycle ^= trb[3].get_bit(1);
+        } else {
+            ring.pointer += 16;
+            self.complete_command(pointer, trb);
+        }
+
+        true
+    }
+
+    unsafe fn complete_command(&mut self, pointer: u64, trb: [u32; 4]) {
+        let (code, slot_id) = if trb[3].get_bits(10..=15) == Type::EnableSlot as u32 {
+            self.allocate_slot()
+        } else {
+            (CompletionCode::Success, trb[3].get_bits(24..=31))
+        };
+
+        self.push_event([
+            pointer.get_bits(0..32).try_into().unwrap(),
+            pointer.get_bits(32..64).try_into().unwrap(),
+            (code as u32) << 24,
+            (Type::CommandCompletion as u32) << 10 | slot_id << 24,
+        ]);
+    }
+
+    fn allocate_slot(&mut self) -> (CompletionCode, u32) {
+        if self.next_slot_id > MAX_SLOTS {
+            (CompletionCode::NoSlotsAvailableError, 0)
+        } else {
+            self.next_slot_id += 1;
+            (CompletionCode::Success, (self.next_slot_id - 1).into())
+        }
+    }
+
+    unsafe fn push_event(&mut self, mut trb: [u32; 4]) {
+        let Some(ring) = &mut self.event_ring else {
+            return;
+        };
+
+        trb[3].set_bit(0, ring.cursor.cycle);
+        write_trb(ring.cursor.pointer, trb);
+
+        ring.cursor.pointer += 16;
+        if ring.cursor.pointer == ring.base + ring.len * 16 {
+            ring.cursor.pointer = ring.base;
+            ring.cursor.cycle = !ring.cursor.cycle;
+        }
+    }
+
+    fn read(&self, offset: usize) -> u32 {
+        // SAFETY: `offset` is in the range of `Mmio`.
+        unsafe { ptr::read_volatile(self.base.add(offset / 4)) }
+    }
+
+    fn read_u64(&self, offset: usize) -> u64 {
+        u64::from(self.read(offset)) | u64::from(self.read(offset + 4)) << 32
+    }
+
+    fn write(&mut self, offset: usize, v: u32) {
+        // SAFETY: `offset` is in the range of `Mmio`.
+        unsafe { ptr::write_volatile(self.base.add(offset / 4), v) }
+    }
+
+    fn update<F>(&mut self, offset: usize, f: F)
+    where
+        F: FnOnce(&mut u32),
+    {
+        let mut v = self.read(offset);
+        f(&mut v);
+        self.write(offset, v);
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Cursor {
+    pointer: u64,
+    cycle: bool,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct EventRing {
+    base: u64,
+    len: u64,
+    cursor: Cursor,
+}
+
+unsafe fn read_trb(addr: u64) -> [u32; 4] {
+    ptr::read_volatile(usize::try_from(addr).unwrap() as *const [u32; 4])
+}
+
+unsafe fn write_trb(addr: u64, trb: [u32; 4]) {
+    ptr::write_volatile(usize::try_from(addr).unwrap() as *mut [u32; 4], trb);
+}
@@ -0,0 +1,196 @@
+//! The error type.
+
+use crate::{
+    registers::operational::Timeout,
+    ring::trb::{command::BuildError, event::CompletionCode, ConversionError},
+};
+use core::fmt;
+
+/// Errors reported by the xHC or detected by this crate.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Error {
+    /// A TRB completed with this Completion Code.
+    CompletionCode(CompletionCode),
+    /// The pointer is not aligned to `alignment` bytes.
+    MisalignedPointer {
+        /// The given pointer.
+        pointer: u64,
+        /// The required alignment in bytes.
+        alignment: u64,
+    },
+    /// The xHC does not support the capability, for example, `"64-bit addressing"`.
+    UnsupportedCapability(&'static str),
+    /// The TRB is malformed or has an unexpected type.
+    InvalidTrb([u32; 4]),
+    /// The Command TRB cannot be built by [`crate::ring::trb::command::Builder`].
+    InvalidCommand(BuildError),
+    /// The index is out of the range `0..len`.
+    IndexOutOfRange {
+        /// The given index.
+        index: usize,
+        /// The number of the elements.
+        len: usize,
+    },
+    /// A condition was not met in time.
+    Timeout,
+    /// The xHC stopped because of a fatal error.
+    ControllerFatal {
+        /// The value of the Host System Error bit of the USB Status Register.
+        host_system_error: bool,
+        /// The value of the Host Controller Error bit of the USB Status Register.
+        host_controller_error: bool,
+    },
+}
+impl Error {
+    /// Returns the action which is recommended to recover from this error.
+    ///
+    /// Returns [`None`] if no recovery action is needed. In this case the failed operation may
+    /// simply be retried or reported to the caller.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::{error::RecoveryAction, ring::trb::event::CompletionCode, Error};
+    ///
+    /// let e = Error::from(CompletionCode::StallError);
+    /// assert_eq!(e.recovery_action(), Some(RecoveryAction::ResetEndpoint));
+    /// ```
+    #[must_use]
+    pub fn recovery_action(self) -> Option<RecoveryAction> {
+        let c = match self {
+            Self::CompletionCode(c) => c,
+            Self::ControllerFatal { .. } => return Some(RecoveryAction::Fatal),
+            _ => return None,
+        };
+
+        if HALTS_ENDPOINT.contains(&c) {
+            Some(RecoveryAction::ResetEndpoint)
+        } else if REQUIRES_DEVICE_RESET.contains(&c) {
+            Some(RecoveryAction::ResetDevice)
+        } else if FATAL.contains(&c) {
+            Some(RecoveryAction::Fatal)
+        } else {
+            None
+        }
+    }
+}
+impl fmt::Display for Error {
+    /// Formats the error for humans.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::Error;
+    ///
+    /// let e = Error::MisalignedPointer {
+    ///     pointer: 0x1008,
+    ///     alignment: 64,
+    /// };
+    /// assert_eq!(
+    ///     format!("{}", e),
+    ///     "the pointer 0x1008 is not aligned to 64 bytes"
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CompletionCode(c) => write!(f, "the TRB completed with {c:?}"),
+            Self::MisalignedPointer { pointer, alignment } => write!(
+                f,
+                "the pointer {pointer:#x} is not aligned to {alignment} bytes"
+            ),
+            Self::UnsupportedCapability(c) => write!(f, "the xHC does not support {c}"),
+            Self::InvalidTrb(raw) => write!(f, "invalid TRB {raw:08x?}"),
+            Self::InvalidCommand(e) => write!(f, "the Command TRB cannot be built: {e:?}"),
+            Self::IndexOutOfRange { index, len } => {
+                write!(f, "the index {index} is out of range for the length {len}")
+            }
+            Self::Timeout => f.write_str("the operation timed out"),
+            Self::ControllerFatal {
+                host_system_error: hse,
+                host_controller_error: hce,
+            } => write!(f, "the xHC stopped (HSE: {hse}, HCE: {hce})"),
+        }
+    }
+}
+impl core::error::Error for Error {}
+impl From<CompletionCode> for Error {
+    /// Converts the Completion Code into an error.
+    ///
+    /// This conversion does not check whether `c` actually represents an error. Call this only
+    /// when the TRB did not complete successfully.
+    fn from(c: CompletionCode) -> Self {
+        Self::CompletionCode(c)
+    }
+}
+
+impl From<ConversionError> for Error {
+    fn from(e: ConversionError) -> Self {
+        Self::InvalidTrb(e.raw())
+    }
+}
+impl From<BuildError> for Error {
+    /// Converts the error of [`crate::ring::trb::command::Builder::build`].
+    ///
+    /// [`BuildError::MisalignedPointer`] is converted into [`Error::MisalignedPointer`], and the
+    /// others are wrapped in [`Error::InvalidCommand`].
+    fn from(e: BuildError) -> Self {
+        match e {
+            BuildError::MisalignedPointer { pointer, alignment } => {
+                Self::MisalignedPointer { pointer, alignment }
+            }
+            _ => Self::InvalidCommand(e),
+        }
+    }
+}
+impl From<Timeout> for Error {
+    fn from(_: Timeout) -> Self {
+        Self::Timeout
+    }
+}
+
+// Returns `Error::MisalignedPointer` if `pointer` is not aligned to `alignment` bytes, which must be
+// a power of two.
+pub(crate) fn check_alignment(pointer: u64, alignment: u64) -> Result<(), Error> {
+    if pointer.trailing_zeros() >= alignment.trailing_zeros() {
+        Ok(())
+    } else {
+        Err(Error::MisalignedPointer { pointer, alignment })
+    }
+}
+
+/// The actions recommended to recover from an [`Error`].
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum RecoveryAction {
+    /// The endpoint is halted. Issue a Reset Endpoint Command and a Set TR Dequeue Pointer
+    /// Command, and clear the halt condition of the device's endpoint if necessary.
+    ResetEndpoint,
+    /// The device cannot be accessed correctly. Issue a Reset Device Command or reset the port to
+    /// which the device is attached.
+    ResetDevice,
+    /// The xHC cannot continue correctly. Reset the host controller.
+    Fatal,
+}
+
+// The Completion Codes which cause the endpoint to transit to the Halted state. See xHCI
+// specification Section 4.10.2.
+const HALTS_ENDPOINT: [CompletionCode; 6] = [
+    CompletionCode::DataBufferError,
+    CompletionCode::BabbleDetectedError,
+    CompletionCode::UsbTransactionError,
+    CompletionCode::TrbError,
+    CompletionCode::StallError,
+    CompletionCode::SplitTransactionError,
+];
+
+const REQUIRES_DEVICE_RESET: [CompletionCode; 2] = [
+    CompletionCode::ContextStateError,
+    CompletionCode::IncompatibleDeviceError,
+];
+
+const FATAL: [CompletionCode; 4] = [
+    CompletionCode::VfEventRingFullError,
+    CompletionCode::EventRingFullError,
+    CompletionCode::EventLostError,
+    CompletionCode::UndefinedError,
+];
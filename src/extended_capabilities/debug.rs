@@ -0,0 +1,1679 @@
+//! Debug Capability
+
+use crate::{
+    context::EndpointHandler,
+    error,
+    quirks::Quirks,
+    registers::{
+        operational::{PortLinkState, Timeout},
+        runtime::{EventRingSegmentTableBaseAddressRegister, EventRingSegmentTableSizeRegister},
+        Register64,
+    },
+    ring::{
+        trb,
+        trb::{
+            event,
+            event::{CompletionCode, TransferEvent},
+            staging::Trb,
+            transfer,
+            transfer::Normal,
+            ConversionError, Link,
+        },
+    },
+    Error,
+};
+use accessor::Mapper;
+use bit_field::BitField;
+use core::{
+    convert::{TryFrom, TryInto},
+    fmt,
+    ops::Range,
+    ptr,
+    sync::atomic,
+};
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+/// The registers of the Debug Capability.
+#[derive(Debug)]
+pub struct Debug<M>
+where
+    M: Mapper + Clone,
+{
+    /// Debug Capability ID Register
+    pub dcid: accessor::Single<IdRegister, M>,
+    /// Debug Capability Doorbell Register
+    pub dcdb: accessor::Single<DoorbellRegister, M>,
+    /// Debug Capability Event Ring Segment Table Size Register
+    pub dcerstsz: accessor::Single<EventRingSegmentTableSizeRegister, M>,
+    /// Debug Capability Event Ring Segment Table Base Address Register
+    pub dcerstba: Register64<EventRingSegmentTableBaseAddressRegister, M>,
+    /// Debug Capability Event Ring Dequeue Pointer Register
+    pub dcerdp: Register64<EventRingDequeuePointerRegister, M>,
+    /// Debug Capability Control Register
+    pub dcctrl: accessor::Single<ControlRegister, M>,
+    /// Debug Capability Status Register
+    pub dcst: accessor::Single<StatusRegister, M>,
+    /// Debug Capability Port Status and Control Register
+    pub dcportsc: accessor::Single<PortStatusAndControlRegister, M>,
+    /// Debug Capability Context Pointer Register
+    pub dccp: Register64<ContextPointerRegister, M>,
+    /// Debug Capability Device Descriptor Info Register 1
+    pub dcddi1: accessor::Single<DeviceDescriptorInfoRegister1, M>,
+    /// Debug Capability Device Descriptor Info Register 2
+    pub dcddi2: accessor::Single<DeviceDescriptorInfoRegister2, M>,
+}
+impl<M> Debug<M>
+where
+    M: Mapper + Clone,
+{
+    /// Creates a new accessor to the Debug Capability.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the capability is accessed only through the returned accessor,
+    /// and that `base` is the address of a Debug Capability.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `base` is not aligned correctly.
+    pub unsafe fn new(base: usize, mapper: &M) -> Self {
+        macro_rules! m {
+            ($offset:expr) => {
+                accessor::Single::new(base + $offset, mapper.clone())
+            };
+        }
+
+        Self {
+            dcid: m!(0x00),
+            dcdb: m!(0x04),
+            dcerstsz: m!(0x08),
+            dcerstba: Register64::new(base + 0x10, mapper.clone()),
+            dcerdp: Register64::new(base + 0x18, mapper.clone()),
+            dcctrl: m!(0x20),
+            dcst: m!(0x24),
+            dcportsc: m!(0x28),
+            dccp: Register64::new(base + 0x30, mapper.clone()),
+            dcddi1: m!(0x38),
+            dcddi2: m!(0x3c),
+        }
+    }
+
+    /// Makes the accessors to the 64-bit registers work around `quirks`.
+    ///
+    /// See [`Register64`] for the affected writes.
+    #[must_use]
+    pub fn with_quirks(self, quirks: Quirks) -> Self {
+        Self {
+            dcerstba: self.dcerstba.with_quirks(quirks),
+            dcerdp: self.dcerdp.with_quirks(quirks),
+            dccp: self.dccp.with_quirks(quirks),
+            ..self
+        }
+    }
+
+    /// Programs the Event Ring, the Debug Capability Context, and the device descriptor
+    /// information.
+    ///
+    /// The Debug Capability must be disabled when this method is called.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `c.erst_base` is not 64 byte aligned, or `c.dequeue_pointer` or
+    /// `c.context_pointer` is not 16 byte aligned. No register is written in this case.
+    pub fn configure(&mut self, c: &Configuration) {
+        assert!(
+            c.erst_base.trailing_zeros() >= 6,
+            "The Event Ring Segment Table must be 64 byte aligned."
+        );
+        assert!(
+            c.dequeue_pointer.trailing_zeros() >= 4,
+            "The Event Ring Dequeue Pointer must be 16 byte aligned."
+        );
+        assert!(
+            c.context_pointer.trailing_zeros() >= 4,
+            "The Debug Capability Context must be 16 byte aligned."
+        );
+
+        self.write_event_ring(c);
+        self.dccp.update(|p| p.set(c.context_pointer));
+        self.write_device_descriptor_info(c);
+    }
+
+    /// Initializes the Debug Capability and waits for the debug host to configure it.
+    ///
+    /// This method calls [`Debug::configure`], sets the Debug Capability Enable bit, and waits
+    /// for the DbC Run bit to be set. `delay` is called while waiting, and it must return `false`
+    /// to give up.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `delay` returns `false` before the debug host configures
+    /// the Debug Capability. The Debug Capability stays enabled in this case, so the caller may
+    /// check [`Debug::status`] later.
+    ///
+    /// # Panics
+    ///
+    /// This method panics for the same reasons as [`Debug::configure`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::{
+    /// #     accessor::Mapper, extended_capabilities, extended_capabilities::ExtendedCapability,
+    /// # };
+    /// use xhci::extended_capabilities::debug::Configuration;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_start: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_start: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// # fn wait_1ms() {}
+    /// let r = unsafe { xhci::Registers::new(MMIO_BASE, mapper.clone()) };
+    /// let l = unsafe {
+    ///     extended_capabilities::List::new(MMIO_BASE, r.capability.hccparams1.read(), mapper)
+    /// };
+    ///
+    /// for e in &mut l.unwrap() {
+    ///     if let Ok(ExtendedCapability::Debug(mut d)) = e {
+    ///         let c = Configuration {
+    ///             erst_base: 0x10_0000,
+    ///             erst_size: 1,
+    ///             dequeue_pointer: 0x20_0000,
+    ///             context_pointer: 0x30_0000,
+    ///             dbc_protocol: 0,
+    ///             vendor_id: 0x1d6b,
+    ///             product_id: 0x0010,
+    ///             device_revision: 0x0001,
+    ///         };
+    ///
+    ///         let mut remaining_ms = 10_000;
+    ///         let r = d.initialize(&c, || {
+    ///             wait_1ms();
+    ///             remaining_ms -= 1;
+    ///             remaining_ms > 0
+    ///         });
+    ///
+    ///         if r.is_err() {
+    ///             // No debug host configured the Debug Capability.
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn initialize<D>(&mut self, c: &Configuration, mut delay: D) -> Result<(), Timeout>
+    where
+        D: FnMut() -> bool,
+    {
+        self.configure(c);
+        self.dcctrl.update(|c| c.set_debug_capability_enable(true));
+
+        while !self.dcctrl.read().dbc_run() {
+            if !delay() {
+                return Err(Timeout);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the current state of the Debug Capability, determined from DCCTRL and DCPORTSC.
+    #[must_use]
+    pub fn status(&self) -> Status {
+        let c = self.dcctrl.read();
+        let p = self.dcportsc.read();
+
+        if !c.debug_capability_enable() || !p.current_connect_status() {
+            Status::Disconnected
+        } else if p.port_config_error_change()
+            || matches!(
+                p.port_link_state(),
+                Err(_) | Ok(PortLinkState::Inactive | PortLinkState::ComplianceMode)
+            )
+        {
+            Status::Error
+        } else if c.dbc_run() {
+            Status::Configured
+        } else {
+            Status::Enumerating
+        }
+    }
+
+    /// Rings the Debug Capability Doorbell for the endpoint `target`.
+    ///
+    /// Call this after enqueuing TDs with [`TransferRing::enqueue`].
+    pub fn ring_doorbell(&mut self, target: DoorbellTarget) {
+        self.dcdb.write(DoorbellRegister::new(target));
+    }
+
+    fn write_event_ring(&mut self, c: &Configuration) {
+        self.dcerstsz.update(|s| s.set(c.erst_size));
+        self.dcerdp.update(|e| {
+            e.set_dequeue_erst_segment_index(0);
+            e.set_event_ring_dequeue_pointer(c.dequeue_pointer);
+        });
+        self.dcerstba.update(|b| b.set(c.erst_base));
+    }
+
+    fn write_device_descriptor_info(&mut self, c: &Configuration) {
+        self.dcddi1.update(|d| {
+            d.set_dbc_protocol(c.dbc_protocol);
+            d.set_vendor_id(c.vendor_id);
+        });
+        self.dcddi2.update(|d| {
+            d.set_product_id(c.product_id);
+            d.set_device_revision(c.device_revision);
+        });
+    }
+}
+
+/// The values to initialize the Debug Capability with [`Debug::configure`] or
+/// [`Debug::initialize`].
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Configuration {
+    /// The address of the Event Ring Segment Table. It must be 64 byte aligned.
+    pub erst_base: u64,
+    /// The number of the entries of the Event Ring Segment Table.
+    pub erst_size: u16,
+    /// The initial Event Ring Dequeue Pointer. It must be 16 byte aligned.
+    pub dequeue_pointer: u64,
+    /// The address of the Debug Capability Context. It must be 16 byte aligned.
+    pub context_pointer: u64,
+    /// The value of the DbC Protocol field.
+    pub dbc_protocol: u8,
+    /// The Vendor ID reported to the debug host.
+    pub vendor_id: u16,
+    /// The Product ID reported to the debug host.
+    pub product_id: u16,
+    /// The Device Revision reported to the debug host.
+    pub device_revision: u16,
+}
+
+/// The state of the Debug Capability.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Status {
+    /// The Debug Capability is disabled, or no debug host is connected.
+    Disconnected,
+    /// A debug host is connected, but it has not configured the Debug Capability yet.
+    Enumerating,
+    /// The debug host has configured the Debug Capability, and the DbC Run bit is set.
+    Configured,
+    /// The port failed to configure the link, or the link is in an error state.
+    Error,
+}
+
+/// A Transfer Ring of a bulk endpoint of the Debug Capability.
+///
+/// The ring consists of a single segment, and its last TRB is a Link TRB which points to the first
+/// one. One TRB is always left unused to distinguish a full ring from an empty one.
+///
+/// # Examples
+///
+/// ```
+/// use xhci::{
+///     context::DebugCapability,
+///     extended_capabilities::debug::{DoorbellTarget, TransferRing},
+///     ring::trb::staging::Trb,
+/// };
+///
+/// # let phys_base = 0x10_0000;
+/// # let buffer_phys = 0x20_0000;
+/// let mut trbs = [Trb::default(); 16];
+/// let mut out = unsafe { TransferRing::new(&mut trbs, phys_base, DoorbellTarget::DataOut) };
+///
+/// let mut c = DebugCapability::new();
+/// out.set_up_endpoint(c.endpoint_out_mut());
+///
+/// let last_trb = out.enqueue(buffer_phys, 512).unwrap();
+/// // Ring the doorbell with `out.doorbell_target()`, and wait for the Transfer Event.
+/// out.mark_completed(last_trb);
+/// ```
+#[derive(Debug)]
+pub struct TransferRing<'a> {
+    trbs: &'a mut [Trb],
+    phys_base: u64,
+    target: DoorbellTarget,
+    enqueue: usize,
+    dequeue: usize,
+    cycle: bool,
+}
+impl<'a> TransferRing<'a> {
+    const MAX_TRB_TRANSFER_LENGTH: u64 = 0x1_0000;
+
+    /// Creates a new Transfer Ring on `trbs`.
+    ///
+    /// All the TRBs are initialized, and the Producer Cycle State is set to 1.
+    ///
+    /// # Safety
+    ///
+    /// `phys_base` must be the physical address of `trbs`, and the memory must be accessible by
+    /// the xHC.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `trbs` has fewer than 3 TRBs, or if `phys_base` is not 16 byte
+    /// aligned.
+    pub unsafe fn new(trbs: &'a mut [Trb], phys_base: u64, target: DoorbellTarget) -> Self {
+        assert!(
+            trbs.len() >= 3,
+            "A Transfer Ring must have at least 3 TRBs."
+        );
+        assert!(
+            phys_base.trailing_zeros() >= 4,
+            "A Transfer Ring must be 16 byte aligned."
+        );
+
+        let mut r = Self {
+            trbs,
+            phys_base,
+            target,
+            enqueue: 0,
+            dequeue: 0,
+            cycle: true,
+        };
+
+        r.clear();
+        r
+    }
+
+    /// Returns the doorbell target of the endpoint of this ring.
+    #[must_use]
+    pub fn doorbell_target(&self) -> DoorbellTarget {
+        self.target
+    }
+
+    /// Returns the physical address of the TRB which the next TD is written to.
+    #[must_use]
+    pub fn enqueue_pointer(&self) -> u64 {
+        self.address_of(self.enqueue)
+    }
+
+    /// Returns the Producer Cycle State.
+    #[must_use]
+    pub fn cycle_state(&self) -> bool {
+        self.cycle
+    }
+
+    /// Writes the TR Dequeue Pointer and the Dequeue Cycle State of this ring to `ep`.
+    ///
+    /// Call this before the Debug Capability is enabled.
+    pub fn set_up_endpoint(&self, ep: &mut dyn EndpointHandler) {
+        ep.set_transfer_ring_dequeue_pointer(self.enqueue_pointer());
+        ep.set_dequeue_cycle_state(self.cycle);
+    }
+
+    /// Enqueues a TD which transfers `len` bytes from or to the buffer at the physical address
+    /// `buffer`.
+    ///
+    /// The buffer is split into Normal TRBs at the boundaries of 65536 bytes, and they are chained. The
+    /// Interrupt On Completion bit of the last TRB is set. This method returns the physical
+    /// address of the last TRB, which is the TRB Pointer of the Transfer Event of the TD.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the ring does not have enough room for the TD. No TRB is
+    /// enqueued in this case.
+    pub fn enqueue(&mut self, buffer: u64, len: u32) -> Result<u64, RingFull> {
+        let n = Self::num_trbs(buffer, len);
+        if n > self.free_trbs() {
+            return Err(RingFull);
+        }
+
+        let end = buffer + u64::from(len);
+        let mut p = buffer;
+        let mut last = self.enqueue_pointer();
+
+        for i in 0..n {
+            let chunk_end =
+                end.min((p / Self::MAX_TRB_TRANSFER_LENGTH + 1) * Self::MAX_TRB_TRANSFER_LENGTH);
+            let chain = i + 1 < n;
+
+            let mut t = Normal::new();
+            t.set_data_buffer_pointer(p)
+                .set_trb_transfer_length((chunk_end - p).try_into().unwrap())
+                .set_chain_bit(chain)
+                .set_interrupt_on_completion(!chain);
+
+            last = self.push(Trb::from(transfer::Allowed::Normal(t)).into_raw(), chain);
+            p = chunk_end;
+        }
+
+        Ok(last)
+    }
+
+    /// Marks the TRBs up to and including the one at the physical address `trb_pointer` as
+    /// processed by the xHC, so that they can be reused.
+    ///
+    /// `trb_pointer` is usually the TRB Pointer field of a Transfer Event TRB.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `trb_pointer` does not point to a TRB of this ring.
+    pub fn mark_completed(&mut self, trb_pointer: u64) {
+        let i = trb_pointer
+            .checked_sub(self.phys_base)
+            .filter(|o| o % 16 == 0)
+            .and_then(|o| usize::try_from(o / 16).ok())
+            .filter(|i| *i < self.link_index());
+        let i = i.expect("The TRB Pointer does not point to a TRB of this ring.");
+
+        self.dequeue = (i + 1) % self.link_index();
+    }
+
+    /// Discards all the TDs, and initializes the ring as [`TransferRing::new`] does.
+    ///
+    /// The xHC must not be processing this ring. The Endpoint Context must point to the first TRB
+    /// with the Dequeue Cycle State 1 before the xHC uses this ring again.
+    pub fn reset(&mut self) {
+        self.enqueue = 0;
+        self.dequeue = 0;
+        self.cycle = true;
+        self.clear();
+    }
+
+    /// Returns `true` if all the enqueued TDs are marked as completed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.enqueue == self.dequeue
+    }
+
+    fn clear(&mut self) {
+        for i in 0..self.link_index() {
+            self.write(i, [0; 4]);
+        }
+        self.write_link(false);
+    }
+
+    fn num_trbs(buffer: u64, len: u32) -> usize {
+        if len == 0 {
+            1
+        } else {
+            let first = buffer / Self::MAX_TRB_TRANSFER_LENGTH;
+            let last = (buffer + u64::from(len) - 1) / Self::MAX_TRB_TRANSFER_LENGTH;
+            (last - first + 1).try_into().unwrap()
+        }
+    }
+
+    fn free_trbs(&self) -> usize {
+        let n = self.link_index();
+        let used = (self.enqueue + n - self.dequeue) % n;
+        n - 1 - used
+    }
+
+    fn push(&mut self, mut raw: [u32; 4], chain: bool) -> u64 {
+        let a = self.address_of(self.enqueue);
+
+        raw[3].set_bit(0, self.cycle);
+        self.write(self.enqueue, raw);
+
+        self.enqueue += 1;
+        if self.enqueue == self.link_index() {
+            self.write_link(chain);
+
+            self.enqueue = 0;
+            self.cycle = !self.cycle;
+        }
+
+        a
+    }
+
+    // The Cycle Bit of the Link TRB is the Producer Cycle State so that the xHC follows the link
+    // after the last TRB of the segment.
+    fn write_link(&mut self, chain: bool) {
+        let mut link = Link::new();
+        link.set_ring_segment_pointer(self.phys_base)
+            .set_toggle_cycle(true)
+            .set_chain_bit(chain)
+            .set_cycle_bit(self.cycle);
+        self.write(self.link_index(), link.into_raw());
+    }
+
+    // Writes the dword which contains the Cycle Bit last so that the xHC does not see a partially
+    // written TRB.
+    fn write(&mut self, i: usize, raw: [u32; 4]) {
+        write_trb(&mut self.trbs[i], raw);
+    }
+
+    fn address_of(&self, i: usize) -> u64 {
+        self.phys_base + u64::try_from(i * trb::BYTES).unwrap()
+    }
+
+    fn link_index(&self) -> usize {
+        self.trbs.len() - 1
+    }
+}
+
+/// The error returned when a Transfer Ring does not have enough room.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct RingFull;
+
+/// The Event Ring of the Debug Capability.
+///
+/// The ring consists of a single segment. Write [`EventRing::segment_table_entry`] to the only
+/// entry of the Event Ring Segment Table.
+#[derive(Debug)]
+pub struct EventRing<'a> {
+    trbs: &'a mut [Trb],
+    phys_base: u64,
+    dequeue: usize,
+    cycle: bool,
+}
+impl<'a> EventRing<'a> {
+    /// Creates a new Event Ring on `trbs`.
+    ///
+    /// All the TRBs are cleared, and the Consumer Cycle State is set to 1.
+    ///
+    /// # Safety
+    ///
+    /// `phys_base` must be the physical address of `trbs`, and the memory must be accessible by
+    /// the xHC.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of the TRBs is not in the range of 16..=4096, or if
+    /// `phys_base` is not 64 byte aligned.
+    pub unsafe fn new(trbs: &'a mut [Trb], phys_base: u64) -> Self {
+        assert!(
+            (16..=4096).contains(&trbs.len()),
+            "An Event Ring Segment must have 16 to 4096 TRBs."
+        );
+        assert!(
+            phys_base.trailing_zeros() >= 6,
+            "An Event Ring Segment must be 64 byte aligned."
+        );
+
+        for t in trbs.iter_mut() {
+            Trb::default().write_volatile(t);
+        }
+
+        Self {
+            trbs,
+            phys_base,
+            dequeue: 0,
+            cycle: true,
+        }
+    }
+
+    /// Returns the raw Event Ring Segment Table Entry which describes this ring.
+    #[must_use]
+    pub fn segment_table_entry(&self) -> [u32; 4] {
+        [
+            self.phys_base.get_bits(0..32).try_into().unwrap(),
+            self.phys_base.get_bits(32..64).try_into().unwrap(),
+            self.trbs.len().try_into().unwrap(),
+            0,
+        ]
+    }
+
+    /// Returns the physical address of the TRB which is dequeued next.
+    ///
+    /// Write this to the Debug Capability Event Ring Dequeue Pointer Register after handling the
+    /// events.
+    #[must_use]
+    pub fn dequeue_pointer(&self) -> u64 {
+        self.phys_base + u64::try_from(self.dequeue * trb::BYTES).unwrap()
+    }
+
+    /// Dequeues an Event TRB.
+    ///
+    /// This method returns [`None`] if the xHC has not written a new Event TRB yet. An Event TRB
+    /// which cannot be parsed is returned as [`Err`].
+    pub fn pop(&mut self) -> Option<Result<event::Allowed, ConversionError>> {
+        let raw = self.read()?;
+
+        self.dequeue += 1;
+        if self.dequeue == self.trbs.len() {
+            self.dequeue = 0;
+            self.cycle = !self.cycle;
+        }
+
+        Some(event::Allowed::try_from(raw))
+    }
+
+    /// Writes `e` to the ring as if the xHC wrote it.
+    ///
+    /// This method is intended for testing the event handling without hardware. The Cycle Bit of
+    /// `e` is set properly. Do not call this method while the xHC is using this ring.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the ring is full of the events which are not dequeued.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::convert::TryFrom;
+    /// use xhci::{
+    ///     extended_capabilities::debug::EventRing,
+    ///     ring::trb::{event::Allowed, staging::Trb},
+    /// };
+    ///
+    /// #[repr(C, align(64))]
+    /// struct Segment([Trb; 16]);
+    ///
+    /// let mut segment = Segment([Trb::default(); 16]);
+    /// let mut ring = unsafe { EventRing::new(&mut segment.0, 0x1000) };
+    ///
+    /// // A Port Status Change Event TRB of the port 1.
+    /// let e = Allowed::try_from([1 << 24, 0, 1 << 24, 34 << 10]).unwrap();
+    /// ring.inject(e).unwrap();
+    ///
+    /// assert!(matches!(ring.pop(), Some(Ok(Allowed::PortStatusChange(_)))));
+    /// assert!(ring.pop().is_none());
+    /// ```
+    pub fn inject(&mut self, mut e: event::Allowed) -> Result<(), RingFull> {
+        let (mut i, mut cycle) = (self.dequeue, self.cycle);
+
+        for _ in 0..self.trbs.len() {
+            if self.cycle_bit_at(i) != cycle {
+                e.set_cycle_bit(cycle);
+                write_trb(&mut self.trbs[i], e.into_raw());
+                return Ok(());
+            }
+
+            i += 1;
+            if i == self.trbs.len() {
+                i = 0;
+                cycle = !cycle;
+            }
+        }
+
+        Err(RingFull)
+    }
+
+    fn cycle_bit_at(&self, i: usize) -> bool {
+        let p = ptr::addr_of!(self.trbs[i]).cast::<u32>();
+
+        // SAFETY: `p` points to a TRB, which consists of 4 dwords.
+        unsafe { ptr::read_volatile(p.add(3)).get_bit(0) }
+    }
+
+    // Reads the dword which contains the Cycle Bit first so that a partially written TRB is not
+    // read.
+    fn read(&self) -> Option<[u32; 4]> {
+        let p = ptr::addr_of!(self.trbs[self.dequeue]).cast::<u32>();
+
+        // SAFETY: `p` points to a TRB, which consists of 4 dwords.
+        unsafe {
+            let d3 = ptr::read_volatile(p.add(3));
+            if d3.get_bit(0) != self.cycle {
+                return None;
+            }
+            atomic::fence(atomic::Ordering::SeqCst);
+
+            Some([
+                ptr::read_volatile(p),
+                ptr::read_volatile(p.add(1)),
+                ptr::read_volatile(p.add(2)),
+                d3,
+            ])
+        }
+    }
+}
+
+// Writes the dword which contains the Cycle Bit last so that the TRB is not read partially.
+fn write_trb(trb: &mut Trb, raw: [u32; 4]) {
+    let p = ptr::addr_of_mut!(*trb).cast::<u32>();
+
+    // SAFETY: `p` points to a TRB, which consists of 4 dwords.
+    unsafe {
+        for (j, d) in raw.iter().enumerate().take(3) {
+            ptr::write_volatile(p.add(j), *d);
+        }
+        atomic::fence(atomic::Ordering::SeqCst);
+        ptr::write_volatile(p.add(3), raw[3]);
+    }
+}
+
+/// A buffer which the xHC reads data from or writes data to.
+#[derive(Debug)]
+pub struct DataBuffer<'a> {
+    /// The buffer.
+    pub data: &'a mut [u8],
+    /// The physical address of `data`.
+    pub phys_base: u64,
+}
+
+/// The rings and the buffers used by [`ByteStream`].
+#[derive(Debug)]
+pub struct ByteStreamParts<'a> {
+    /// The Transfer Ring of the OUT Bulk Endpoint.
+    pub out_ring: TransferRing<'a>,
+    /// The Transfer Ring of the IN Bulk Endpoint.
+    pub in_ring: TransferRing<'a>,
+    /// The Event Ring of the Debug Capability.
+    pub event_ring: EventRing<'a>,
+    /// The buffer which data is sent from.
+    pub out_buffer: DataBuffer<'a>,
+    /// The buffer which data is received into.
+    pub in_buffer: DataBuffer<'a>,
+}
+
+/// A non-blocking byte stream over the bulk endpoints of the Debug Capability.
+///
+/// At most one TD is in flight on each endpoint. [`ByteStream::write`] copies the data into the
+/// OUT buffer and sends it, and [`ByteStream::read`] returns the data received into the IN
+/// buffer. Both methods handle the completed TDs first, and return immediately.
+///
+/// The buffers and the rings must be in memory which is coherent with the xHC.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use core::num::NonZeroUsize;
+/// # use xhci::accessor::Mapper;
+/// use xhci::extended_capabilities::debug::{ByteStream, ByteStreamParts, Debug, StreamEvent};
+/// #
+/// # #[derive(Clone, Debug)]
+/// # struct MemoryMapper;
+/// # impl Mapper for MemoryMapper {
+/// #     unsafe fn map(&mut self, phys_start: usize, bytes: usize) -> NonZeroUsize {
+/// #         unimplemented!()
+/// #     }
+/// #
+/// #     fn unmap(&mut self, virt_start: usize, bytes: usize) {
+/// #         unimplemented!()
+/// #     }
+/// # }
+/// #
+/// # fn get_dbc() -> Debug<MemoryMapper> { unimplemented!() }
+/// # fn get_parts() -> ByteStreamParts<'static> { unimplemented!() }
+/// // `dbc` is initialized with `Debug::initialize`.
+/// let dbc = get_dbc();
+/// let mut s = ByteStream::new(dbc, get_parts());
+///
+/// let mut remaining: &[u8] = b"Hello, world!";
+/// while !remaining.is_empty() {
+///     let n = s.write(remaining);
+///     remaining = &remaining[n..];
+/// }
+///
+/// let mut buf = [0; 64];
+/// let n = s.read(&mut buf);
+///
+/// while let Some(e) = s.next_event() {
+///     if e == StreamEvent::Disconnected {
+///         // Wait for `StreamEvent::Configured` before writing again.
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ByteStream<'a, M>
+where
+    M: Mapper + Clone,
+{
+    dbc: Debug<M>,
+    event_ring: EventRing<'a>,
+    tx: Channel<'a>,
+    rx: Channel<'a>,
+    received: Range<usize>,
+    events: EventQueue,
+}
+impl<'a, M> ByteStream<'a, M>
+where
+    M: Mapper + Clone,
+{
+    /// Creates a new byte stream.
+    ///
+    /// The Transfer Rings and the Event Ring in `parts` must be the ones programmed to `dbc`.
+    #[must_use]
+    pub fn new(dbc: Debug<M>, parts: ByteStreamParts<'a>) -> Self {
+        Self {
+            dbc,
+            event_ring: parts.event_ring,
+            tx: Channel::new(parts.out_ring, parts.out_buffer),
+            rx: Channel::new(parts.in_ring, parts.in_buffer),
+            received: 0..0,
+            events: EventQueue::new(),
+        }
+    }
+
+    /// Sends the bytes of `data` as many as the OUT buffer can hold.
+    ///
+    /// This method returns the number of the bytes accepted, which is 0 if the previous data is
+    /// still being sent or if the Debug Capability is not running.
+    pub fn write(&mut self, data: &[u8]) -> usize {
+        self.poll();
+
+        if self.tx.pending.is_some() || self.tx.halted || !self.dbc.dcctrl.read().dbc_run() {
+            return 0;
+        }
+
+        let n = data.len().min(self.tx.capacity());
+        if n == 0 {
+            return 0;
+        }
+
+        self.tx.buffer.data[..n].copy_from_slice(&data[..n]);
+        atomic::fence(atomic::Ordering::SeqCst);
+
+        if self.tx.submit(n) {
+            self.dbc.ring_doorbell(DoorbellTarget::DataOut);
+            n
+        } else {
+            0
+        }
+    }
+
+    /// Copies the received bytes into `buf`, and returns the number of the copied bytes.
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        self.poll();
+
+        let n = buf.len().min(self.received.len());
+        let start = self.received.start;
+        buf[..n].copy_from_slice(&self.rx.buffer.data[start..start + n]);
+        self.received.start += n;
+
+        self.submit_in_td();
+        n
+    }
+
+    /// Returns `true` if the data written by [`ByteStream::write`] is still being sent.
+    #[must_use]
+    pub fn is_writing(&self) -> bool {
+        self.tx.pending.is_some()
+    }
+
+    /// Handles the Event TRBs and the state changes of the Debug Capability, and queues a TD to
+    /// the IN Bulk Endpoint if all the received data is read.
+    ///
+    /// The state changes are reported through [`ByteStream::next_event`].
+    pub fn poll(&mut self) {
+        self.handle_event_trbs();
+        self.handle_port_changes();
+        self.handle_run_change();
+        self.handle_halts();
+        self.submit_in_td();
+    }
+
+    /// Returns the oldest event which is not returned yet.
+    ///
+    /// Up to 8 events are kept, and the later ones are dropped until the events are taken.
+    pub fn next_event(&mut self) -> Option<StreamEvent> {
+        self.events.pop()
+    }
+
+    /// Returns a mutable reference to the registers of the Debug Capability.
+    pub fn debug_capability(&mut self) -> &mut Debug<M> {
+        &mut self.dbc
+    }
+
+    fn handle_event_trbs(&mut self) {
+        let mut handled = false;
+
+        while let Some(e) = self.event_ring.pop() {
+            handled = true;
+
+            if let Ok(event::Allowed::TransferEvent(t)) = e {
+                self.on_transfer_event(&t);
+            }
+        }
+
+        if handled {
+            let p = self.event_ring.dequeue_pointer();
+            self.dbc
+                .dcerdp
+                .update(|r| r.set_event_ring_dequeue_pointer(p));
+        }
+    }
+
+    // A TD which failed, for example, with a Stall Error is retired, and its data is dropped.
+    fn on_transfer_event(&mut self, t: &TransferEvent) {
+        let c = t.completion_code();
+        let succeeded = matches!(c, Ok(CompletionCode::Success | CompletionCode::ShortPacket));
+
+        if self.tx.complete(t.trb_pointer()).is_some() && !succeeded {
+            self.events
+                .push(StreamEvent::TransferFailed(DoorbellTarget::DataOut, c));
+        }
+
+        if let Some(requested) = self.rx.complete(t.trb_pointer()) {
+            let n = if succeeded {
+                t.actual_transfer_length(requested).unwrap_or(0)
+            } else {
+                self.events
+                    .push(StreamEvent::TransferFailed(DoorbellTarget::DataIn, c));
+                0
+            };
+            self.received = 0..n.try_into().unwrap();
+            atomic::fence(atomic::Ordering::SeqCst);
+        }
+    }
+
+    // After the cable is unplugged or the debug host resets the port, the rings are reset so that
+    // the xHC starts from their first TRBs when the debug host configures the Debug Capability
+    // again.
+    fn handle_port_changes(&mut self) {
+        let p = self.dbc.dcportsc.read();
+
+        if p.connect_status_change() {
+            self.dbc
+                .dcportsc
+                .update(PortStatusAndControlRegister::clear_connect_status_change);
+        }
+        if p.port_reset_change() {
+            self.dbc
+                .dcportsc
+                .update(PortStatusAndControlRegister::clear_port_reset_change);
+        }
+
+        if (p.connect_status_change() && !p.current_connect_status()) || p.port_reset_change() {
+            self.tx.reset();
+            self.rx.reset();
+            self.received = 0..0;
+            self.events.push(StreamEvent::Disconnected);
+        }
+    }
+
+    fn handle_run_change(&mut self) {
+        let c = self.dbc.dcctrl.read();
+
+        if c.dbc_run_change() {
+            self.dbc
+                .dcctrl
+                .update(ControlRegister::clear_dbc_run_change);
+
+            if c.dbc_run() {
+                self.events.push(StreamEvent::Configured);
+            }
+        }
+    }
+
+    // The doorbell is rung again after the halt is cleared so that the xHC restarts the TD in
+    // flight.
+    fn handle_halts(&mut self) {
+        let c = self.dbc.dcctrl.read();
+
+        if c.halt_out_tr() {
+            self.dbc.dcctrl.update(ControlRegister::clear_halt_out_tr);
+        }
+        if c.halt_in_tr() {
+            self.dbc.dcctrl.update(ControlRegister::clear_halt_in_tr);
+        }
+
+        self.update_halt(DoorbellTarget::DataOut, c.halt_out_tr());
+        self.update_halt(DoorbellTarget::DataIn, c.halt_in_tr());
+    }
+
+    fn update_halt(&mut self, target: DoorbellTarget, halted: bool) {
+        let ch = match target {
+            DoorbellTarget::DataOut => &mut self.tx,
+            DoorbellTarget::DataIn => &mut self.rx,
+        };
+
+        if halted && !ch.halted {
+            ch.halted = true;
+            self.events.push(StreamEvent::Halted(target));
+        } else if !halted && ch.halted {
+            ch.halted = false;
+            if ch.pending.is_some() {
+                self.dbc.ring_doorbell(target);
+            }
+            self.events.push(StreamEvent::Resumed(target));
+        }
+    }
+
+    fn submit_in_td(&mut self) {
+        if self.received.is_empty()
+            && self.dbc.dcctrl.read().dbc_run()
+            && self.rx.submit(self.rx.capacity())
+        {
+            self.dbc.ring_doorbell(DoorbellTarget::DataIn);
+        }
+    }
+}
+
+/// The events reported by [`ByteStream::next_event`].
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum StreamEvent {
+    /// The debug host configured the Debug Capability.
+    Configured,
+    /// The cable was unplugged, or the debug host reset the port. The data in flight is dropped,
+    /// and the Transfer Rings are reset. The stream is usable again after
+    /// [`StreamEvent::Configured`] is reported.
+    Disconnected,
+    /// The endpoint was halted.
+    Halted(DoorbellTarget),
+    /// The halt of the endpoint was cleared, and the transfer is restarted.
+    Resumed(DoorbellTarget),
+    /// A TD failed with the Completion Code, and its data is dropped.
+    TransferFailed(DoorbellTarget, Result<CompletionCode, u8>),
+}
+
+#[derive(Debug)]
+struct Channel<'a> {
+    ring: TransferRing<'a>,
+    buffer: DataBuffer<'a>,
+    pending: Option<PendingTd>,
+    halted: bool,
+}
+impl<'a> Channel<'a> {
+    fn new(ring: TransferRing<'a>, buffer: DataBuffer<'a>) -> Self {
+        Self {
+            ring,
+            buffer,
+            pending: None,
+            halted: false,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.ring.reset();
+        self.pending = None;
+        self.halted = false;
+    }
+
+    fn capacity(&self) -> usize {
+        self.buffer.data.len().min(u32::MAX.try_into().unwrap())
+    }
+
+    // Returns `false` if a TD is already in flight or the ring is full.
+    fn submit(&mut self, len: usize) -> bool {
+        if self.pending.is_some() {
+            return false;
+        }
+
+        let len = len.try_into().unwrap();
+        match self.ring.enqueue(self.buffer.phys_base, len) {
+            Ok(last_trb) => {
+                self.pending = Some(PendingTd { last_trb, len });
+                true
+            }
+            Err(RingFull) => false,
+        }
+    }
+
+    // Returns the length of the TD if its last TRB is at `trb_pointer`.
+    fn complete(&mut self, trb_pointer: u64) -> Option<u32> {
+        let p = self.pending.filter(|p| p.last_trb == trb_pointer)?;
+
+        self.ring.mark_completed(trb_pointer);
+        self.pending = None;
+        Some(p.len)
+    }
+}
+
+#[derive(Debug)]
+struct EventQueue {
+    events: [Option<StreamEvent>; 8],
+    head: usize,
+    len: usize,
+}
+impl EventQueue {
+    fn new() -> Self {
+        Self {
+            events: [None; 8],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, e: StreamEvent) {
+        if self.len < self.events.len() {
+            self.events[(self.head + self.len) % self.events.len()] = Some(e);
+            self.len += 1;
+        }
+    }
+
+    fn pop(&mut self) -> Option<StreamEvent> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let e = self.events[self.head].take();
+        self.head = (self.head + 1) % self.events.len();
+        self.len -= 1;
+        e
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct PendingTd {
+    last_trb: u64,
+    len: u32,
+}
+
+/// Debug Capability ID Register
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct IdRegister(u32);
+impl IdRegister {
+    /// Returns the value of the Debug Capability Event Ring Segment Table Max field.
+    ///
+    /// The maximum number of the Event Ring Segment Table entries is `2^dcerst_max`.
+    #[must_use]
+    pub fn dcerst_max(self) -> u8 {
+        self.0.get_bits(16..=20).try_into().unwrap()
+    }
+}
+impl fmt::Debug for IdRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("debug::IdRegister")
+            .field("dcerst_max", &self.dcerst_max())
+            .finish()
+    }
+}
+
+/// Debug Capability Doorbell Register
+///
+/// This register always reads as zero. Write the whole value instead of updating the read one.
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct DoorbellRegister(u32);
+impl DoorbellRegister {
+    /// Creates a value of the register with the DB Target `target`.
+    #[must_use]
+    pub fn new(target: DoorbellTarget) -> Self {
+        let mut r = Self(0);
+        r.set_doorbell_target(target);
+        r
+    }
+
+    /// Returns the value of the DB Target field.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the raw value as an error if it is reserved.
+    pub fn doorbell_target(self) -> Result<DoorbellTarget, u8> {
+        let t: u8 = self.0.get_bits(8..=15).try_into().unwrap();
+        DoorbellTarget::from_u8(t).ok_or(t)
+    }
+
+    /// Sets the value of the DB Target field.
+    pub fn set_doorbell_target(&mut self, t: DoorbellTarget) {
+        self.0.set_bits(8..=15, t as u32);
+    }
+}
+impl fmt::Debug for DoorbellRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("debug::DoorbellRegister")
+            .field("doorbell_target", &self.doorbell_target())
+            .finish()
+    }
+}
+
+/// The value of the DB Target field of the Debug Capability Doorbell Register.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, FromPrimitive)]
+pub enum DoorbellTarget {
+    /// Data EP 1 OUT Enqueue Pointer Update.
+    DataOut = 0,
+    /// Data EP 1 IN Enqueue Pointer Update.
+    DataIn = 1,
+}
+
+/// Debug Capability Event Ring Dequeue Pointer Register
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct EventRingDequeuePointerRegister(u64);
+impl EventRingDequeuePointerRegister {
+    /// Returns the value of the Dequeue ERST Segment Index field.
+    #[must_use]
+    pub fn dequeue_erst_segment_index(self) -> u8 {
+        self.0.get_bits(0..=2).try_into().unwrap()
+    }
+
+    /// Sets the value of the Dequeue ERST Segment Index field.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `i` is larger than 7.
+    pub fn set_dequeue_erst_segment_index(&mut self, i: u8) {
+        assert!(i < 8, "Dequeue ERST Segment Index must be less than 8.");
+        self.0.set_bits(0..=2, i.into());
+    }
+
+    /// Returns the address of the current Event Ring Dequeue Pointer.
+    #[must_use]
+    pub fn event_ring_dequeue_pointer(self) -> u64 {
+        self.0 & !0b1111
+    }
+
+    /// Sets the address of the current Event Ring Dequeue Pointer. It must be 16 byte aligned.
+    ///
+    /// This method keeps the Dequeue ERST Segment Index field.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the address is not 16 byte aligned. Use
+    /// [`EventRingDequeuePointerRegister::try_set_event_ring_dequeue_pointer`] to handle the
+    /// error.
+    pub fn set_event_ring_dequeue_pointer(&mut self, p: u64) {
+        assert!(
+            p.trailing_zeros() >= 4,
+            "The Event Ring Dequeue Pointer must be 16 byte aligned."
+        );
+        self.0 = p | self.0.get_bits(0..=2);
+    }
+
+    /// Sets the address of the current Event Ring Dequeue Pointer without panicking.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::MisalignedPointer`] if the address is not 16 byte aligned.
+    /// The register is not changed in this case.
+    pub fn try_set_event_ring_dequeue_pointer(&mut self, p: u64) -> Result<(), Error> {
+        error::check_alignment(p, 16)?;
+        self.set_event_ring_dequeue_pointer(p);
+        Ok(())
+    }
+}
+impl From<EventRingDequeuePointerRegister> for u64 {
+    fn from(e: EventRingDequeuePointerRegister) -> Self {
+        e.0
+    }
+}
+impl From<u64> for EventRingDequeuePointerRegister {
+    fn from(raw: u64) -> Self {
+        Self(raw)
+    }
+}
+impl fmt::Debug for EventRingDequeuePointerRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("debug::EventRingDequeuePointerRegister")
+            .field(
+                "dequeue_erst_segment_index",
+                &self.dequeue_erst_segment_index(),
+            )
+            .field(
+                "event_ring_dequeue_pointer",
+                &self.event_ring_dequeue_pointer(),
+            )
+            .finish()
+    }
+}
+
+/// Debug Capability Control Register
+///
+/// The Halt OUT TR, the Halt IN TR, and the DbC Run Change bits are cleared by writing 1. The
+/// setters of this struct write 0 to these bits so that writing back the read value does not
+/// clear them.
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct ControlRegister(u32);
+impl ControlRegister {
+    /// Returns the value of the DbC Run bit.
+    #[must_use]
+    pub fn dbc_run(self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// Returns the value of the Link Status Event Enable bit.
+    #[must_use]
+    pub fn link_status_event_enable(self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    /// Sets the value of the Link Status Event Enable bit.
+    pub fn set_link_status_event_enable(&mut self, b: bool) {
+        self.clear_write_one_bits_except(None);
+        self.0.set_bit(1, b);
+    }
+
+    /// Returns the value of the Halt OUT TR bit.
+    #[must_use]
+    pub fn halt_out_tr(self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    /// Clears the Halt OUT TR bit.
+    pub fn clear_halt_out_tr(&mut self) {
+        self.clear_write_one_bits_except(Some(2));
+    }
+
+    /// Returns the value of the Halt IN TR bit.
+    #[must_use]
+    pub fn halt_in_tr(self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    /// Clears the Halt IN TR bit.
+    pub fn clear_halt_in_tr(&mut self) {
+        self.clear_write_one_bits_except(Some(3));
+    }
+
+    /// Returns the value of the DbC Run Change bit.
+    #[must_use]
+    pub fn dbc_run_change(self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    /// Clears the DbC Run Change bit.
+    pub fn clear_dbc_run_change(&mut self) {
+        self.clear_write_one_bits_except(Some(4));
+    }
+
+    /// Returns the value of the Debug Max Burst Size field.
+    #[must_use]
+    pub fn debug_max_burst_size(self) -> u8 {
+        self.0.get_bits(16..=23).try_into().unwrap()
+    }
+
+    /// Returns the value of the Device Address field.
+    #[must_use]
+    pub fn device_address(self) -> u8 {
+        self.0.get_bits(24..=30).try_into().unwrap()
+    }
+
+    /// Returns the value of the Debug Capability Enable bit.
+    #[must_use]
+    pub fn debug_capability_enable(self) -> bool {
+        self.0.get_bit(31)
+    }
+
+    /// Sets the value of the Debug Capability Enable bit.
+    pub fn set_debug_capability_enable(&mut self, b: bool) {
+        self.clear_write_one_bits_except(None);
+        self.0.set_bit(31, b);
+    }
+
+    fn clear_write_one_bits_except(&mut self, i: Option<usize>) {
+        for b in 2..=4 {
+            self.0.set_bit(b, Some(b) == i);
+        }
+    }
+}
+impl fmt::Debug for ControlRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("debug::ControlRegister")
+            .field("dbc_run", &self.dbc_run())
+            .field("link_status_event_enable", &self.link_status_event_enable())
+            .field("halt_out_tr", &self.halt_out_tr())
+            .field("halt_in_tr", &self.halt_in_tr())
+            .field("dbc_run_change", &self.dbc_run_change())
+            .field("debug_max_burst_size", &self.debug_max_burst_size())
+            .field("device_address", &self.device_address())
+            .field("debug_capability_enable", &self.debug_capability_enable())
+            .finish()
+    }
+}
+
+/// Debug Capability Status Register
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct StatusRegister(u32);
+impl StatusRegister {
+    /// Returns the value of the Event Ring Not Empty bit.
+    #[must_use]
+    pub fn event_ring_not_empty(self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// Returns the value of the DbC System Bus Reset bit.
+    #[must_use]
+    pub fn dbc_system_bus_reset(self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    /// Returns the value of the Debug Port Number field.
+    #[must_use]
+    pub fn debug_port_number(self) -> u8 {
+        self.0.get_bits(24..=31).try_into().unwrap()
+    }
+}
+impl fmt::Debug for StatusRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("debug::StatusRegister")
+            .field("event_ring_not_empty", &self.event_ring_not_empty())
+            .field("dbc_system_bus_reset", &self.dbc_system_bus_reset())
+            .field("debug_port_number", &self.debug_port_number())
+            .finish()
+    }
+}
+
+/// Debug Capability Port Status and Control Register
+///
+/// The Connect Status Change, the Port Reset Change, the Port Link Status Change, and the Port
+/// Config Error Change bits are cleared by writing 1. The setters of this struct write 0 to these
+/// bits so that writing back the read value does not clear them.
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct PortStatusAndControlRegister(u32);
+impl PortStatusAndControlRegister {
+    const WRITE_ONE_TO_CLEAR_BITS: [usize; 4] = [17, 21, 22, 23];
+
+    /// Returns the value of the Current Connect Status bit.
+    #[must_use]
+    pub fn current_connect_status(self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// Returns the value of the Port Enabled/Disabled bit.
+    #[must_use]
+    pub fn port_enabled_disabled(self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    /// Sets the value of the Port Enabled/Disabled bit.
+    pub fn set_port_enabled_disabled(&mut self, b: bool) {
+        self.clear_write_one_bits_except(None);
+        self.0.set_bit(1, b);
+    }
+
+    /// Returns the value of the Port Reset bit.
+    #[must_use]
+    pub fn port_reset(self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    /// Returns the value of the Port Link State field.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the raw value as an error if it is reserved.
+    pub fn port_link_state(self) -> Result<PortLinkState, u8> {
+        let s: u8 = self.0.get_bits(5..=8).try_into().unwrap();
+        PortLinkState::from_u8(s).ok_or(s)
+    }
+
+    /// Returns the value of the Port Speed field.
+    #[must_use]
+    pub fn port_speed(self) -> u8 {
+        self.0.get_bits(10..=13).try_into().unwrap()
+    }
+
+    /// Returns the value of the Connect Status Change bit.
+    #[must_use]
+    pub fn connect_status_change(self) -> bool {
+        self.0.get_bit(17)
+    }
+
+    /// Clears the Connect Status Change bit.
+    pub fn clear_connect_status_change(&mut self) {
+        self.clear_write_one_bits_except(Some(17));
+    }
+
+    /// Returns the value of the Port Reset Change bit.
+    #[must_use]
+    pub fn port_reset_change(self) -> bool {
+        self.0.get_bit(21)
+    }
+
+    /// Clears the Port Reset Change bit.
+    pub fn clear_port_reset_change(&mut self) {
+        self.clear_write_one_bits_except(Some(21));
+    }
+
+    /// Returns the value of the Port Link Status Change bit.
+    #[must_use]
+    pub fn port_link_status_change(self) -> bool {
+        self.0.get_bit(22)
+    }
+
+    /// Clears the Port Link Status Change bit.
+    pub fn clear_port_link_status_change(&mut self) {
+        self.clear_write_one_bits_except(Some(22));
+    }
+
+    /// Returns the value of the Port Config Error Change bit.
+    #[must_use]
+    pub fn port_config_error_change(self) -> bool {
+        self.0.get_bit(23)
+    }
+
+    /// Clears the Port Config Error Change bit.
+    pub fn clear_port_config_error_change(&mut self) {
+        self.clear_write_one_bits_except(Some(23));
+    }
+
+    fn clear_write_one_bits_except(&mut self, i: Option<usize>) {
+        for b in &Self::WRITE_ONE_TO_CLEAR_BITS {
+            self.0.set_bit(*b, Some(*b) == i);
+        }
+    }
+}
+impl fmt::Debug for PortStatusAndControlRegister {
+    // Unavoidable because the register has many fields.
+    #[allow(clippy::too_many_lines)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("debug::PortStatusAndControlRegister")
+            .field("current_connect_status", &self.current_connect_status())
+            .field("port_enabled_disabled", &self.port_enabled_disabled())
+            .field("port_reset", &self.port_reset())
+            .field("port_link_state", &self.port_link_state())
+            .field("port_speed", &self.port_speed())
+            .field("connect_status_change", &self.connect_status_change())
+            .field("port_reset_change", &self.port_reset_change())
+            .field("port_link_status_change", &self.port_link_status_change())
+            .field("port_config_error_change", &self.port_config_error_change())
+            .finish()
+    }
+}
+
+/// Debug Capability Context Pointer Register
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct ContextPointerRegister(u64);
+impl ContextPointerRegister {
+    /// Returns the address of the Debug Capability Context data structure.
+    #[must_use]
+    pub fn get(self) -> u64 {
+        self.0 & !0b1111
+    }
+
+    /// Sets the address of the Debug Capability Context data structure. It must be 16 byte
+    /// aligned.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the address is not 16 byte aligned. Use
+    /// [`ContextPointerRegister::try_set`] to handle the error.
+    pub fn set(&mut self, a: u64) {
+        assert!(
+            a.trailing_zeros() >= 4,
+            "The Debug Capability Context must be 16 byte aligned."
+        );
+        self.0 = a;
+    }
+
+    /// Sets the address of the Debug Capability Context data structure without panicking.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::MisalignedPointer`] if the address is not 16 byte aligned.
+    /// The register is not changed in this case.
+    pub fn try_set(&mut self, a: u64) -> Result<(), Error> {
+        error::check_alignment(a, 16)?;
+        self.set(a);
+        Ok(())
+    }
+}
+impl From<ContextPointerRegister> for u64 {
+    fn from(c: ContextPointerRegister) -> Self {
+        c.0
+    }
+}
+impl From<u64> for ContextPointerRegister {
+    fn from(raw: u64) -> Self {
+        Self(raw)
+    }
+}
+impl fmt::Debug for ContextPointerRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("debug::ContextPointerRegister")
+            .field(&self.get())
+            .finish()
+    }
+}
+
+/// Debug Capability Device Descriptor Info Register 1
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct DeviceDescriptorInfoRegister1(u32);
+impl DeviceDescriptorInfoRegister1 {
+    /// Returns the value of the DbC Protocol field.
+    #[must_use]
+    pub fn dbc_protocol(self) -> u8 {
+        self.0.get_bits(0..=7).try_into().unwrap()
+    }
+
+    /// Sets the value of the DbC Protocol field.
+    pub fn set_dbc_protocol(&mut self, p: u8) {
+        self.0.set_bits(0..=7, p.into());
+    }
+
+    /// Returns the value of the Vendor ID field.
+    #[must_use]
+    pub fn vendor_id(self) -> u16 {
+        self.0.get_bits(16..=31).try_into().unwrap()
+    }
+
+    /// Sets the value of the Vendor ID field.
+    pub fn set_vendor_id(&mut self, id: u16) {
+        self.0.set_bits(16..=31, id.into());
+    }
+}
+impl fmt::Debug for DeviceDescriptorInfoRegister1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("debug::DeviceDescriptorInfoRegister1")
+            .field("dbc_protocol", &self.dbc_protocol())
+            .field("vendor_id", &self.vendor_id())
+            .finish()
+    }
+}
+
+/// Debug Capability Device Descriptor Info Register 2
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct DeviceDescriptorInfoRegister2(u32);
+impl DeviceDescriptorInfoRegister2 {
+    /// Returns the value of the Product ID field.
+    #[must_use]
+    pub fn product_id(self) -> u16 {
+        self.0.get_bits(0..=15).try_into().unwrap()
+    }
+
+    /// Sets the value of the Product ID field.
+    pub fn set_product_id(&mut self, id: u16) {
+        self.0.set_bits(0..=15, id.into());
+    }
+
+    /// Returns the value of the Device Revision field.
+    #[must_use]
+    pub fn device_revision(self) -> u16 {
+        self.0.get_bits(16..=31).try_into().unwrap()
+    }
+
+    /// Sets the value of the Device Revision field.
+    pub fn set_device_revision(&mut self, r: u16) {
+        self.0.set_bits(16..=31, r.into());
+    }
+}
+impl fmt::Debug for DeviceDescriptorInfoRegister2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("debug::DeviceDescriptorInfoRegister2")
+            .field("product_id", &self.product_id())
+            .field("device_revision", &self.device_revision())
+            .finish()
+    }
+}
@@ -0,0 +1,221 @@
+//! Extended Power Management Capability
+//!
+//! The format of this capability is the same as the PCI Power Management Capability. It allows
+//! software which does not access the PCI Configuration Space to read and set the power state of
+//! the xHC.
+
+use accessor::Mapper;
+use bit_field::BitField;
+use core::{convert::TryInto, fmt};
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+/// The registers of the Extended Power Management Capability.
+#[derive(Debug)]
+pub struct ExtendedPowerManagement<M>
+where
+    M: Mapper + Clone,
+{
+    /// Power Management Capabilities Register
+    pub pmc: accessor::Single<PowerManagementCapabilities, M>,
+    /// Power Management Control/Status Register
+    pub pmcsr: accessor::Single<PowerManagementControlStatus, M>,
+}
+impl<M> ExtendedPowerManagement<M>
+where
+    M: Mapper + Clone,
+{
+    /// Creates a new accessor to the Extended Power Management Capability.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the capability is accessed only through the returned accessor,
+    /// and that `base` is the address of an Extended Power Management Capability.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `base` is not aligned correctly.
+    pub unsafe fn new(base: usize, mapper: M) -> Self {
+        Self {
+            pmc: accessor::Single::new(base, mapper.clone()),
+            pmcsr: accessor::Single::new(base + 4, mapper),
+        }
+    }
+}
+
+/// Power Management Capabilities Register
+///
+/// This struct also contains the Capability ID and the Next Capability Pointer fields, which
+/// occupy the lower 16 bits.
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct PowerManagementCapabilities(u32);
+impl PowerManagementCapabilities {
+    /// Returns the value of the Version field.
+    #[must_use]
+    pub fn version(self) -> u8 {
+        self.0.get_bits(16..=18).try_into().unwrap()
+    }
+
+    /// Returns the value of the PME Clock bit.
+    #[must_use]
+    pub fn pme_clock(self) -> bool {
+        self.0.get_bit(19)
+    }
+
+    /// Returns the value of the Device Specific Initialization bit.
+    #[must_use]
+    pub fn device_specific_initialization(self) -> bool {
+        self.0.get_bit(21)
+    }
+
+    /// Returns the value of the Aux Current field.
+    #[must_use]
+    pub fn aux_current(self) -> u8 {
+        self.0.get_bits(22..=24).try_into().unwrap()
+    }
+
+    /// Returns the value of the D1 Support bit.
+    #[must_use]
+    pub fn d1_support(self) -> bool {
+        self.0.get_bit(25)
+    }
+
+    /// Returns the value of the D2 Support bit.
+    #[must_use]
+    pub fn d2_support(self) -> bool {
+        self.0.get_bit(26)
+    }
+
+    /// Returns the value of the PME Support field.
+    ///
+    /// Bit 0 to 4 of the returned value represent whether PME# can be asserted from D0, D1, D2,
+    /// D3hot, and D3cold respectively.
+    #[must_use]
+    pub fn pme_support(self) -> u8 {
+        self.0.get_bits(27..=31).try_into().unwrap()
+    }
+}
+impl fmt::Debug for PowerManagementCapabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PowerManagementCapabilities")
+            .field("version", &self.version())
+            .field("pme_clock", &self.pme_clock())
+            .field(
+                "device_specific_initialization",
+                &self.device_specific_initialization(),
+            )
+            .field("aux_current", &self.aux_current())
+            .field("d1_support", &self.d1_support())
+            .field("d2_support", &self.d2_support())
+            .field("pme_support", &self.pme_support())
+            .finish()
+    }
+}
+
+/// Power Management Control/Status Register
+///
+/// This struct also contains the Bridge Support Extensions and the Data fields, which occupy the
+/// upper 16 bits.
+///
+/// The PME Status bit is cleared by writing 1. The setters of this struct write 0 to this bit so
+/// that writing back the read value does not clear it.
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct PowerManagementControlStatus(u32);
+impl PowerManagementControlStatus {
+    /// Returns the value of the Power State field.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the raw value as an error if it is reserved.
+    pub fn power_state(self) -> Result<PowerState, u8> {
+        let s: u8 = self.0.get_bits(0..=1).try_into().unwrap();
+        PowerState::from_u8(s).ok_or(s)
+    }
+
+    /// Sets the value of the Power State field.
+    pub fn set_power_state(&mut self, s: PowerState) {
+        self.0.set_bit(15, false);
+        self.0.set_bits(0..=1, s as u32);
+    }
+
+    /// Returns the value of the No Soft Reset bit.
+    #[must_use]
+    pub fn no_soft_reset(self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    /// Returns the value of the PME Enable bit.
+    #[must_use]
+    pub fn pme_enable(self) -> bool {
+        self.0.get_bit(8)
+    }
+
+    /// Sets the value of the PME Enable bit.
+    pub fn set_pme_enable(&mut self, b: bool) {
+        self.0.set_bit(15, false);
+        self.0.set_bit(8, b);
+    }
+
+    /// Returns the value of the Data Select field.
+    #[must_use]
+    pub fn data_select(self) -> u8 {
+        self.0.get_bits(9..=12).try_into().unwrap()
+    }
+
+    /// Sets the value of the Data Select field.
+    pub fn set_data_select(&mut self, s: u8) {
+        self.0.set_bit(15, false);
+        self.0.set_bits(9..=12, s.into());
+    }
+
+    /// Returns the value of the Data Scale field.
+    #[must_use]
+    pub fn data_scale(self) -> u8 {
+        self.0.get_bits(13..=14).try_into().unwrap()
+    }
+
+    /// Returns the value of the PME Status bit.
+    #[must_use]
+    pub fn pme_status(self) -> bool {
+        self.0.get_bit(15)
+    }
+
+    /// Clears the PME Status bit.
+    pub fn clear_pme_status(&mut self) {
+        self.0.set_bit(15, true);
+    }
+
+    /// Returns the value of the Data field.
+    #[must_use]
+    pub fn data(self) -> u8 {
+        self.0.get_bits(24..=31).try_into().unwrap()
+    }
+}
+impl fmt::Debug for PowerManagementControlStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PowerManagementControlStatus")
+            .field("power_state", &self.power_state())
+            .field("no_soft_reset", &self.no_soft_reset())
+            .field("pme_enable", &self.pme_enable())
+            .field("data_select", &self.data_select())
+            .field("data_scale", &self.data_scale())
+            .field("pme_status", &self.pme_status())
+            .field("data", &self.data())
+            .finish()
+    }
+}
+
+/// The power states of the xHC.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, FromPrimitive)]
+pub enum PowerState {
+    /// D0 state.
+    D0 = 0,
+    /// D1 state.
+    D1 = 1,
+    /// D2 state.
+    D2 = 2,
+    /// D3hot state.
+    D3Hot = 3,
+}
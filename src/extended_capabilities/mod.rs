@@ -38,10 +38,14 @@
 //!             match e {
 //!                 Ok(e) => match e {
 //!                     ExtendedCapability::UsbLegacySupportCapability(u) => {}
+//!                     ExtendedCapability::Unknown { id, raw } => {
+//!                         // Currently this crate does not support this Extended Capability.
+//!                         // `raw` allows accessing its dwords.
+//!                     }
 //!                     _ => {}
 //!                 },
 //!                 Err(e) => {
-//!                     // Currently this crate does not support this Extended Capability.
+//!                     // The list is broken.
 //!                 }
 //!             }
 //!         }
@@ -52,13 +56,18 @@
 //! }
 //! ```
 
-use super::registers::capability::CapabilityParameters1;
+use super::{quirks::Quirks, registers::capability::CapabilityParameters1};
 use accessor::Mapper;
 use bit_field::BitField;
-use core::convert::TryInto;
+use core::convert::{TryFrom, TryInto};
 
-pub use usb_legacy_support_capability::UsbLegacySupportCapability;
+pub use extended_power_management::ExtendedPowerManagement;
+pub use supported_protocol::SupportedProtocol;
+pub use usb_legacy_support_capability::{UsbLegacySupport, UsbLegacySupportCapability};
 
+pub mod debug;
+pub mod extended_power_management;
+pub mod supported_protocol;
 pub mod usb_legacy_support_capability;
 
 /// A struct to access xHCI Extended Capabilities.
@@ -67,13 +76,18 @@ pub struct List<M>
 where
     M: Mapper + Clone,
 {
+    mmio_base: usize,
     base: usize,
+    end: usize,
     m: M,
+    quirks: Quirks,
 }
 impl<M> List<M>
 where
     M: Mapper + Clone,
 {
+    const HCCPARAMS1_OFFSET: usize = 0x10;
+
     /// Creates a new accessor to the xHCI Extended Capabilities.
     ///
     /// This method may return a [`None`] value if the xHC does not support the xHCI Extended
@@ -124,15 +138,172 @@ where
             None
         } else {
             let base = mmio_base + (xecp << 2);
-            Some(Self { base, m: mapper })
+            Some(Self {
+                mmio_base,
+                base,
+                end: usize::MAX,
+                m: mapper,
+                quirks: Quirks::empty(),
+            })
         }
     }
+
+    /// Creates a new accessor to the xHCI Extended Capabilities, reading the Capability Parameters
+    /// 1 Register by itself.
+    ///
+    /// Unlike [`List::new`], this method does not need [`Registers`](crate::Registers). Only the
+    /// Capability Parameters 1 Register and the Extended Capabilities are mapped, which is useful
+    /// to use the Debug Capability before the xHC driver is initialized.
+    ///
+    /// This method may return a [`None`] value if the xHC does not support the xHCI Extended
+    /// Capabilities.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `mmio_base` is the base address of the xHC's MMIO region, and
+    /// that each of the xHCI Extended Capabilities is accessed only through the returned accessor.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// use xhci::extended_capabilities::{debug, List};
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_start: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_start: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let dbc = unsafe { List::from_mmio_base(MMIO_BASE, mapper) }
+    ///     .and_then(|mut l| l.find::<debug::Debug<_>>());
+    /// ```
+    pub unsafe fn from_mmio_base(mmio_base: usize, mapper: M) -> Option<Self> {
+        let hccparams1 = accessor::Single::<CapabilityParameters1, M>::new(
+            mmio_base + Self::HCCPARAMS1_OFFSET,
+            mapper.clone(),
+        )
+        .read();
+
+        Self::new(mmio_base, hccparams1, mapper)
+    }
+
+    /// Limits the xHCI Extended Capabilities to the MMIO region whose length is `mmio_len` bytes.
+    ///
+    /// The iterator returns [`IterError::OutOfRange`] and stops if an Extended Capability is
+    /// outside the region, instead of accessing the address.
+    #[must_use]
+    pub fn with_mmio_len(self, mmio_len: usize) -> Self {
+        Self {
+            end: self.mmio_base.saturating_add(mmio_len),
+            ..self
+        }
+    }
+
+    /// Makes the returned Extended Capabilities work around `quirks`.
+    ///
+    /// Currently only [`debug::Debug`] is affected. See [`debug::Debug::with_quirks`].
+    #[must_use]
+    pub fn with_quirks(self, quirks: Quirks) -> Self {
+        Self { quirks, ..self }
+    }
+
+    /// Returns the first Extended Capability of the type `T`.
+    ///
+    /// `T` is one of the types wrapped by the variants of [`ExtendedCapability`]. The errors
+    /// returned by the iterator are ignored, and the search ends at a broken entry of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// use xhci::extended_capabilities::{List, UsbLegacySupport};
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_start: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_start: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let r = unsafe { xhci::Registers::new(MMIO_BASE, mapper.clone()) };
+    /// let mut l = unsafe { List::new(MMIO_BASE, r.capability.hccparams1.read(), mapper) }.unwrap();
+    ///
+    /// if let Some(u) = l.find::<UsbLegacySupport<_>>() {
+    ///     // Take the ownership of the xHC from the BIOS.
+    /// }
+    /// ```
+    pub fn find<T>(&mut self) -> Option<T>
+    where
+        T: TryFrom<ExtendedCapability<M>>,
+    {
+        self.find_all().next()
+    }
+
+    /// Returns an iterator over all the Extended Capabilities of the type `T`.
+    ///
+    /// See [`List::find`] for details.
+    pub fn find_all<T>(&mut self) -> impl Iterator<Item = T>
+    where
+        T: TryFrom<ExtendedCapability<M>>,
+    {
+        self.into_iter().filter_map(|e| T::try_from(e.ok()?).ok())
+    }
+
+    /// Returns the xHCI Supported Protocol Capability of the port with the port number
+    /// `port_number`.
+    ///
+    /// Use [`supported_protocol::Header::major_revision`] to know whether the port is a USB2 or a
+    /// USB3 port. Note that the xHCI specification does not define how USB2 and USB3 ports are
+    /// paired.
+    ///
+    /// This method returns [`None`] if no capability covers the port.
+    pub fn supported_protocol_of(&mut self, port_number: u8) -> Option<SupportedProtocol<M>> {
+        self.find_all::<SupportedProtocol<M>>()
+            .find(|p| p.contains_port(port_number))
+    }
+
+    /// Returns the Protocol Speed ID of the port with the port number `port_number` and the Port
+    /// Speed `port_speed`.
+    ///
+    /// `port_speed` is the value of the Port Speed field of the Port Status and Control Register.
+    /// See [`SupportedProtocol::protocol_speed_id_of`] for details.
+    ///
+    /// This method returns [`None`] if no capability covers the port, or no Protocol Speed ID
+    /// matches.
+    pub fn protocol_speed_id_of(
+        &mut self,
+        port_number: u8,
+        port_speed: u8,
+    ) -> Option<supported_protocol::ProtocolSpeedId> {
+        self.supported_protocol_of(port_number)?
+            .protocol_speed_id_of(port_speed)
+    }
 }
 impl<M> IntoIterator for &mut List<M>
 where
     M: Mapper + Clone,
 {
-    type Item = Result<ExtendedCapability<M>, NotSupportedId>;
+    type Item = Result<ExtendedCapability<M>, IterError>;
     type IntoIter = IterMut<M>;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -141,22 +312,96 @@ where
 }
 
 /// An iterator over the xHCI Extended Capability.
+///
+/// The iteration ends after an error is returned.
 #[derive(Debug)]
 pub struct IterMut<M>
 where
     M: Mapper + Clone,
 {
     current: Option<usize>,
+    end: usize,
+    visited: usize,
     m: M,
+    quirks: Quirks,
 }
 impl<M> IterMut<M>
 where
     M: Mapper + Clone,
 {
+    const MAX_CAPABILITIES: usize = 256;
+    const MAX_UNKNOWN_DWORDS: usize = 256;
+
     fn new(l: &List<M>) -> Self {
         Self {
             current: Some(l.base),
+            end: l.end,
+            visited: 0,
             m: l.m.clone(),
+            quirks: l.quirks,
+        }
+    }
+
+    // The Next xHCI Extended Capability Pointer is never negative, so the addresses always
+    // increase and never form a cycle. Still, a broken xHC may make the list too long.
+    fn check(&self, current: usize) -> Result<(), IterError> {
+        if self.visited >= Self::MAX_CAPABILITIES {
+            return Err(IterError::TooManyCapabilities);
+        }
+
+        match current.checked_add(4) {
+            Some(e) if e <= self.end => Ok(()),
+            _ => Err(IterError::OutOfRange { address: current }),
+        }
+    }
+
+    fn capability(&self, current: usize, h: Header) -> ExtendedCapability<M> {
+        match h.id() {
+            // SAFETY: `List::new` ensures that the all necessary conditions are fulfilled.
+            1 => ExtendedCapability::UsbLegacySupportCapability(unsafe {
+                UsbLegacySupport::new(current, self.m.clone())
+            }),
+            // SAFETY: `List::new` ensures that the all necessary conditions are fulfilled.
+            2 => ExtendedCapability::SupportedProtocol(unsafe {
+                SupportedProtocol::new(current, self.m.clone())
+            }),
+            // SAFETY: `List::new` ensures that the all necessary conditions are fulfilled.
+            3 => ExtendedCapability::ExtendedPowerManagement(unsafe {
+                ExtendedPowerManagement::new(current, self.m.clone())
+            }),
+            // SAFETY: `List::new` ensures that the all necessary conditions are fulfilled.
+            10 => ExtendedCapability::Debug(
+                unsafe { debug::Debug::new(current, &self.m) }.with_quirks(self.quirks),
+            ),
+            id => ExtendedCapability::Unknown {
+                id,
+                // SAFETY: `List::new` ensures that the all necessary conditions are fulfilled, and
+                // `IterMut::unknown_len` keeps the accessor within the capability and the MMIO
+                // region.
+                raw: unsafe {
+                    accessor::Array::new(current, self.unknown_len(current, h), self.m.clone())
+                },
+            },
+        }
+    }
+
+    // The length of an unknown capability is the distance to the next one. The last one is
+    // limited to `MAX_UNKNOWN_DWORDS` dwords and the end of the MMIO region.
+    fn unknown_len(&self, current: usize, h: Header) -> usize {
+        let len = match h.next() {
+            0 => Self::MAX_UNKNOWN_DWORDS,
+            n => n.into(),
+        };
+
+        // `IterMut::check` ensures that at least one dword is in the region.
+        len.min((self.end - current) / 4)
+    }
+
+    fn next_address(current: usize, h: Header) -> Option<usize> {
+        if h.next() == 0 {
+            None
+        } else {
+            current.checked_add(usize::from(h.next()) << 2)
         }
     }
 }
@@ -164,51 +409,103 @@ impl<M> Iterator for IterMut<M>
 where
     M: Mapper + Clone,
 {
-    type Item = Result<ExtendedCapability<M>, NotSupportedId>;
+    type Item = Result<ExtendedCapability<M>, IterError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let current = self.current?;
+        let current = self.current.take()?;
+
+        if let Err(e) = self.check(current) {
+            return Some(Err(e));
+        }
+        self.visited += 1;
 
         // SAFETY: `Iter::new` guarantees that `self.current` is the correct address.
         let h: Header = unsafe { accessor::Single::new(current, self.m.clone()) }.read();
 
-        self.current = if h.next() == 0 {
-            None
-        } else {
-            Some(current + (usize::from(h.next()) << 2))
-        };
+        self.current = Self::next_address(current, h);
 
-        Some(match h.id() {
-            // SAFETY: `List::new` ensures that the all necessary conditions are fulfilled.
-            1 => Ok(ExtendedCapability::UsbLegacySupportCapability(unsafe {
-                accessor::Single::new(current, self.m.clone())
-            })),
-            e => Err(NotSupportedId(e)),
-        })
+        Some(Ok(self.capability(current, h)))
     }
 }
 
 /// The xHCI Extended Capability.
+// The variants cannot be boxed because this crate does not use `alloc`.
+#[allow(clippy::large_enum_variant)]
 #[non_exhaustive]
 #[derive(Debug)]
 pub enum ExtendedCapability<M>
 where
-    M: Mapper,
+    M: Mapper + Clone,
 {
     /// USB Legacy Support Capability.
-    UsbLegacySupportCapability(accessor::Single<UsbLegacySupportCapability, M>),
+    UsbLegacySupportCapability(UsbLegacySupport<M>),
+    /// xHCI Supported Protocol Capability.
+    SupportedProtocol(SupportedProtocol<M>),
+    /// Extended Power Management Capability.
+    ExtendedPowerManagement(ExtendedPowerManagement<M>),
+    /// Debug Capability.
+    Debug(debug::Debug<M>),
+    /// An Extended Capability which this crate does not support, including the vendor defined
+    /// ones.
+    Unknown {
+        /// The Capability ID.
+        id: u8,
+        /// The dwords of the capability. The first one contains the Capability ID and the Next
+        /// Capability Pointer.
+        ///
+        /// The length is the distance to the next capability, or up to 256 dwords if this is the
+        /// last one. It is also limited by [`List::with_mmio_len`].
+        raw: accessor::Array<u32, M>,
+    },
+}
+
+macro_rules! impl_try_from_extended_capability {
+    ($variant:ident, $ty:ty) => {
+        impl<M> TryFrom<ExtendedCapability<M>> for $ty
+        where
+            M: Mapper + Clone,
+        {
+            type Error = ExtendedCapability<M>;
+
+            fn try_from(e: ExtendedCapability<M>) -> Result<Self, Self::Error> {
+                if let ExtendedCapability::$variant(c) = e {
+                    Ok(c)
+                } else {
+                    Err(e)
+                }
+            }
+        }
+    };
+}
+impl_try_from_extended_capability!(UsbLegacySupportCapability, UsbLegacySupport<M>);
+impl_try_from_extended_capability!(SupportedProtocol, SupportedProtocol<M>);
+impl_try_from_extended_capability!(ExtendedPowerManagement, ExtendedPowerManagement<M>);
+impl_try_from_extended_capability!(Debug, debug::Debug<M>);
+
+/// The errors returned by [`IterMut`].
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum IterError {
+    /// The Extended Capability at the address is outside the MMIO region.
+    ///
+    /// This error is returned only if the length of the MMIO region is set by
+    /// [`List::with_mmio_len`].
+    OutOfRange {
+        /// The address of the Extended Capability.
+        address: usize,
+    },
+    /// The list contains too many Extended Capabilities. This usually means that the list is
+    /// broken.
+    TooManyCapabilities,
 }
 
 /// A struct representing that the Extended Capability with the ID is not supported by this crate.
 ///
-/// # Examples
-///
-/// ```
-/// // The Extended Capability with the ID 7 is not supported by this crate.
-/// use xhci::extended_capabilities::NotSupportedId;
-///
-/// NotSupportedId(7);
-/// ```
+/// The iterator of [`List`] no longer returns this type. The unsupported Extended Capabilities
+/// are returned as [`ExtendedCapability::Unknown`].
+#[deprecated(
+    note = "the unsupported Extended Capabilities are returned as `ExtendedCapability::Unknown`"
+)]
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default, Debug)]
 pub struct NotSupportedId(pub u8);
 
@@ -0,0 +1,255 @@
+//! xHCI Supported Protocol Capability
+
+use accessor::Mapper;
+use bit_field::BitField;
+use core::{convert::TryInto, fmt};
+
+/// xHCI Supported Protocol Capability
+#[derive(Debug)]
+pub struct SupportedProtocol<M>
+where
+    M: Mapper + Clone,
+{
+    /// The first 16 bytes of the capability.
+    pub header: accessor::Single<Header, M>,
+    /// Protocol Speed ID (PSI) Dwords.
+    ///
+    /// This is [`None`] if the Protocol Speed ID Count field is zero.
+    pub psis: Option<accessor::Array<ProtocolSpeedId, M>>,
+}
+impl<M> SupportedProtocol<M>
+where
+    M: Mapper + Clone,
+{
+    /// Creates a new accessor to the xHCI Supported Protocol Capability.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the capability is accessed only through the returned accessor,
+    /// and that `base` is the address of an xHCI Supported Protocol Capability.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `base` is not aligned correctly.
+    pub unsafe fn new(base: usize, mapper: M) -> Self {
+        let header: accessor::Single<Header, M> = accessor::Single::new(base, mapper.clone());
+        let count = header.read().protocol_speed_id_count();
+
+        let psis = if count == 0 {
+            None
+        } else {
+            Some(accessor::Array::new(base + 0x10, count.into(), mapper))
+        };
+
+        Self { header, psis }
+    }
+
+    /// Returns `true` if the port with the port number `port_number` supports this protocol.
+    #[must_use]
+    pub fn contains_port(&self, port_number: u8) -> bool {
+        let h = self.header.read();
+        let first = u16::from(h.compatible_port_offset());
+        let last = first + u16::from(h.compatible_port_count());
+
+        (first..last).contains(&port_number.into())
+    }
+
+    /// Returns the Protocol Speed ID whose Protocol Speed ID Value is `port_speed`.
+    ///
+    /// Pass the value of the Port Speed field of the Port Status and Control Register as
+    /// `port_speed`. If the capability has no PSI Dwords, this method returns the Protocol Speed
+    /// ID of the default speed mapping for the Protocol Speed ID Values from 1 to 5, that is,
+    /// Full-speed, Low-speed, High-speed, Gen1 x1, and Gen2 x1.
+    ///
+    /// An asymmetric speed has multiple PSI Dwords with the same Protocol Speed ID Value. This
+    /// method returns the first one.
+    ///
+    /// This method returns [`None`] if no Protocol Speed ID matches.
+    #[must_use]
+    pub fn protocol_speed_id_of(&self, port_speed: u8) -> Option<ProtocolSpeedId> {
+        match &self.psis {
+            Some(psis) => (0..psis.len())
+                .map(|i| psis.read_at(i))
+                .find(|p| p.protocol_speed_id_value() == port_speed),
+            None => ProtocolSpeedId::DEFAULTS
+                .iter()
+                .copied()
+                .find(|p| p.protocol_speed_id_value() == port_speed),
+        }
+    }
+}
+
+/// The first 16 bytes of the xHCI Supported Protocol Capability.
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct Header([u32; 4]);
+impl Header {
+    /// Returns the value of the Minor Revision field.
+    #[must_use]
+    pub fn minor_revision(self) -> u8 {
+        self.0[0].get_bits(16..=23).try_into().unwrap()
+    }
+
+    /// Returns the value of the Major Revision field.
+    ///
+    /// This is `0x02` for USB2 ports and `0x03` for USB3 ports.
+    #[must_use]
+    pub fn major_revision(self) -> u8 {
+        self.0[0].get_bits(24..=31).try_into().unwrap()
+    }
+
+    /// Returns the value of the Name String field.
+    ///
+    /// This is `0x2042_5355`, which is "USB " in ASCII, for the USB protocols.
+    #[must_use]
+    pub fn name_string(self) -> u32 {
+        self.0[1]
+    }
+
+    /// Returns the value of the Compatible Port Offset field.
+    ///
+    /// This is the port number of the first port which supports this protocol.
+    #[must_use]
+    pub fn compatible_port_offset(self) -> u8 {
+        self.0[2].get_bits(0..=7).try_into().unwrap()
+    }
+
+    /// Returns the value of the Compatible Port Count field.
+    #[must_use]
+    pub fn compatible_port_count(self) -> u8 {
+        self.0[2].get_bits(8..=15).try_into().unwrap()
+    }
+
+    /// Returns the value of the Protocol Defined field.
+    #[must_use]
+    pub fn protocol_defined(self) -> u16 {
+        self.0[2].get_bits(16..=27).try_into().unwrap()
+    }
+
+    /// Returns the value of the Protocol Speed ID Count field.
+    #[must_use]
+    pub fn protocol_speed_id_count(self) -> u8 {
+        self.0[2].get_bits(28..=31).try_into().unwrap()
+    }
+
+    /// Returns the value of the Protocol Slot Type field.
+    #[must_use]
+    pub fn protocol_slot_type(self) -> u8 {
+        self.0[3].get_bits(0..=4).try_into().unwrap()
+    }
+}
+impl fmt::Debug for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("supported_protocol::Header")
+            .field("minor_revision", &self.minor_revision())
+            .field("major_revision", &self.major_revision())
+            .field("name_string", &self.name_string())
+            .field("compatible_port_offset", &self.compatible_port_offset())
+            .field("compatible_port_count", &self.compatible_port_count())
+            .field("protocol_defined", &self.protocol_defined())
+            .field("protocol_speed_id_count", &self.protocol_speed_id_count())
+            .field("protocol_slot_type", &self.protocol_slot_type())
+            .finish()
+    }
+}
+
+/// Protocol Speed ID (PSI) Dword
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct ProtocolSpeedId(u32);
+impl ProtocolSpeedId {
+    // Table 7-13 of the xHCI specification.
+    const DEFAULTS: [Self; 5] = [
+        // Full-speed, 12 Mb/s.
+        Self(0x000c_0021),
+        // Low-speed, 1.5 Mb/s.
+        Self(0x05dc_0012),
+        // High-speed, 480 Mb/s.
+        Self(0x01e0_0023),
+        // SuperSpeed Gen1 x1, 5 Gb/s.
+        Self(0x0005_0134),
+        // SuperSpeedPlus Gen2 x1, 10 Gb/s.
+        Self(0x000a_4135),
+    ];
+
+    /// Returns the bit rate of this speed in bits per second.
+    ///
+    /// The bit rate is calculated from the Protocol Speed ID Mantissa and the Protocol Speed ID
+    /// Exponent fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::extended_capabilities::supported_protocol::ProtocolSpeedId;
+    ///
+    /// // 5 Gb/s, SuperSpeed Gen1 x1.
+    /// let p = ProtocolSpeedId::from_raw(0x0005_0134);
+    ///
+    /// assert_eq!(p.bit_rate(), 5_000_000_000);
+    /// ```
+    #[must_use]
+    pub fn bit_rate(self) -> u64 {
+        u64::from(self.protocol_speed_id_mantissa())
+            * 1000_u64.pow(self.protocol_speed_id_exponent().into())
+    }
+
+    /// Creates a value of the PSI Dword from a raw dword.
+    #[must_use]
+    pub const fn from_raw(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the value of the Protocol Speed ID Value field.
+    #[must_use]
+    pub fn protocol_speed_id_value(self) -> u8 {
+        self.0.get_bits(0..=3).try_into().unwrap()
+    }
+
+    /// Returns the value of the Protocol Speed ID Exponent field.
+    #[must_use]
+    pub fn protocol_speed_id_exponent(self) -> u8 {
+        self.0.get_bits(4..=5).try_into().unwrap()
+    }
+
+    /// Returns the value of the PSI Type field.
+    #[must_use]
+    pub fn psi_type(self) -> u8 {
+        self.0.get_bits(6..=7).try_into().unwrap()
+    }
+
+    /// Returns the value of the PSI Full-duplex bit.
+    #[must_use]
+    pub fn psi_full_duplex(self) -> bool {
+        self.0.get_bit(8)
+    }
+
+    /// Returns the value of the Link Protocol field.
+    #[must_use]
+    pub fn link_protocol(self) -> u8 {
+        self.0.get_bits(14..=15).try_into().unwrap()
+    }
+
+    /// Returns the value of the Protocol Speed ID Mantissa field.
+    #[must_use]
+    pub fn protocol_speed_id_mantissa(self) -> u16 {
+        self.0.get_bits(16..=31).try_into().unwrap()
+    }
+}
+impl fmt::Debug for ProtocolSpeedId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProtocolSpeedId")
+            .field("protocol_speed_id_value", &self.protocol_speed_id_value())
+            .field(
+                "protocol_speed_id_exponent",
+                &self.protocol_speed_id_exponent(),
+            )
+            .field("psi_type", &self.psi_type())
+            .field("psi_full_duplex", &self.psi_full_duplex())
+            .field("link_protocol", &self.link_protocol())
+            .field(
+                "protocol_speed_id_mantissa",
+                &self.protocol_speed_id_mantissa(),
+            )
+            .finish()
+    }
+}
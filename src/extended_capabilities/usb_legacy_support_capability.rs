@@ -1,8 +1,116 @@
 //! USB Legacy Support Capability
 
+use crate::registers::operational::Timeout;
+use accessor::Mapper;
 use bit_field::BitField;
 use core::fmt;
 
+/// The registers of the USB Legacy Support Capability.
+#[derive(Debug)]
+pub struct UsbLegacySupport<M>
+where
+    M: Mapper + Clone,
+{
+    /// USB Legacy Support Capability Register
+    pub usblegsup: accessor::Single<UsbLegacySupportCapability, M>,
+    /// USB Legacy Support Control/Status Register
+    pub usblegctlsts: accessor::Single<UsbLegacySupportControlStatus, M>,
+}
+impl<M> UsbLegacySupport<M>
+where
+    M: Mapper + Clone,
+{
+    /// Creates a new accessor to the USB Legacy Support Capability.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the capability is accessed only through the returned accessor,
+    /// and that `base` is the address of a USB Legacy Support Capability.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `base` is not aligned correctly.
+    pub unsafe fn new(base: usize, mapper: M) -> Self {
+        Self {
+            usblegsup: accessor::Single::new(base, mapper.clone()),
+            usblegctlsts: accessor::Single::new(base + 4, mapper),
+        }
+    }
+
+    /// Takes the ownership of the xHC from the BIOS.
+    ///
+    /// This method sets the HC OS Owned Semaphore bit and waits for the BIOS to clear the HC BIOS
+    /// Owned Semaphore bit. `delay` is called while waiting, and it must return `false` to give
+    /// up. After that, this method disables all the SMIs and clears the SMI status bits.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `delay` returns `false` before the BIOS releases the xHC.
+    /// The SMIs are not disabled in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::{
+    /// #     accessor::Mapper, extended_capabilities, extended_capabilities::ExtendedCapability,
+    /// # };
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_start: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_start: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// # fn wait_1ms() {}
+    /// let r = unsafe { xhci::Registers::new(MMIO_BASE, mapper.clone()) };
+    /// let l = unsafe {
+    ///     extended_capabilities::List::new(MMIO_BASE, r.capability.hccparams1.read(), mapper)
+    /// };
+    ///
+    /// for e in &mut l.unwrap() {
+    ///     if let Ok(ExtendedCapability::UsbLegacySupportCapability(mut u)) = e {
+    ///         let mut remaining_ms = 1000;
+    ///         let r = u.request_os_ownership(|| {
+    ///             wait_1ms();
+    ///             remaining_ms -= 1;
+    ///             remaining_ms > 0
+    ///         });
+    ///
+    ///         if r.is_err() {
+    ///             // The BIOS did not release the xHC.
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn request_os_ownership<D>(&mut self, mut delay: D) -> Result<(), Timeout>
+    where
+        D: FnMut() -> bool,
+    {
+        self.usblegsup.update(|u| u.set_hc_os_owned_semaphore(true));
+
+        while self.usblegsup.read().hc_bios_owned_semaphore() {
+            if !delay() {
+                return Err(Timeout);
+            }
+        }
+
+        self.usblegctlsts
+            .update(UsbLegacySupportControlStatus::disable_all_smis);
+
+        Ok(())
+    }
+}
+
 /// USB Legacy Support Capability
 #[repr(transparent)]
 #[derive(Copy, Clone)]
@@ -38,3 +146,170 @@ impl fmt::Debug for UsbLegacySupportCapability {
             .finish()
     }
 }
+
+/// USB Legacy Support Control/Status Register
+///
+/// The SMI on OS Ownership Change, the SMI on PCI Command, and the SMI on BAR bits are cleared by
+/// writing 1. The setters of this struct write 0 to these bits so that writing back the read
+/// value does not clear them.
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct UsbLegacySupportControlStatus(u32);
+impl UsbLegacySupportControlStatus {
+    const WRITE_ONE_TO_CLEAR_BITS: [usize; 3] = [29, 30, 31];
+    const SMI_ENABLE_BITS: [usize; 5] = [0, 4, 13, 14, 15];
+
+    /// Returns the value of the USB SMI Enable bit.
+    #[must_use]
+    pub fn usb_smi_enable(self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// Sets the value of the USB SMI Enable bit.
+    pub fn set_usb_smi_enable(&mut self, b: bool) {
+        self.set_enable_bit(0, b);
+    }
+
+    /// Returns the value of the SMI on Host System Error Enable bit.
+    #[must_use]
+    pub fn smi_on_host_system_error_enable(self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    /// Sets the value of the SMI on Host System Error Enable bit.
+    pub fn set_smi_on_host_system_error_enable(&mut self, b: bool) {
+        self.set_enable_bit(4, b);
+    }
+
+    /// Returns the value of the SMI on OS Ownership Enable bit.
+    #[must_use]
+    pub fn smi_on_os_ownership_enable(self) -> bool {
+        self.0.get_bit(13)
+    }
+
+    /// Sets the value of the SMI on OS Ownership Enable bit.
+    pub fn set_smi_on_os_ownership_enable(&mut self, b: bool) {
+        self.set_enable_bit(13, b);
+    }
+
+    /// Returns the value of the SMI on PCI Command Enable bit.
+    #[must_use]
+    pub fn smi_on_pci_command_enable(self) -> bool {
+        self.0.get_bit(14)
+    }
+
+    /// Sets the value of the SMI on PCI Command Enable bit.
+    pub fn set_smi_on_pci_command_enable(&mut self, b: bool) {
+        self.set_enable_bit(14, b);
+    }
+
+    /// Returns the value of the SMI on BAR Enable bit.
+    #[must_use]
+    pub fn smi_on_bar_enable(self) -> bool {
+        self.0.get_bit(15)
+    }
+
+    /// Sets the value of the SMI on BAR Enable bit.
+    pub fn set_smi_on_bar_enable(&mut self, b: bool) {
+        self.set_enable_bit(15, b);
+    }
+
+    /// Returns the value of the SMI on Event Interrupt bit.
+    #[must_use]
+    pub fn smi_on_event_interrupt(self) -> bool {
+        self.0.get_bit(16)
+    }
+
+    /// Returns the value of the SMI on Host System Error bit.
+    #[must_use]
+    pub fn smi_on_host_system_error(self) -> bool {
+        self.0.get_bit(20)
+    }
+
+    /// Returns the value of the SMI on OS Ownership Change bit.
+    #[must_use]
+    pub fn smi_on_os_ownership_change(self) -> bool {
+        self.0.get_bit(29)
+    }
+
+    /// Clears the SMI on OS Ownership Change bit.
+    pub fn clear_smi_on_os_ownership_change(&mut self) {
+        self.clear_write_one_bits_except(29);
+    }
+
+    /// Returns the value of the SMI on PCI Command bit.
+    #[must_use]
+    pub fn smi_on_pci_command(self) -> bool {
+        self.0.get_bit(30)
+    }
+
+    /// Clears the SMI on PCI Command bit.
+    pub fn clear_smi_on_pci_command(&mut self) {
+        self.clear_write_one_bits_except(30);
+    }
+
+    /// Returns the value of the SMI on BAR bit.
+    #[must_use]
+    pub fn smi_on_bar(self) -> bool {
+        self.0.get_bit(31)
+    }
+
+    /// Clears the SMI on BAR bit.
+    pub fn clear_smi_on_bar(&mut self) {
+        self.clear_write_one_bits_except(31);
+    }
+
+    /// Clears all the SMI enable bits and all the write-1-to-clear SMI status bits.
+    pub fn disable_all_smis(&mut self) {
+        for i in &Self::SMI_ENABLE_BITS {
+            self.0.set_bit(*i, false);
+        }
+
+        for i in &Self::WRITE_ONE_TO_CLEAR_BITS {
+            self.0.set_bit(*i, true);
+        }
+    }
+
+    fn set_enable_bit(&mut self, i: usize, b: bool) {
+        for w in &Self::WRITE_ONE_TO_CLEAR_BITS {
+            self.0.set_bit(*w, false);
+        }
+        self.0.set_bit(i, b);
+    }
+
+    fn clear_write_one_bits_except(&mut self, i: usize) {
+        for b in &Self::WRITE_ONE_TO_CLEAR_BITS {
+            self.0.set_bit(*b, *b == i);
+        }
+    }
+}
+impl fmt::Debug for UsbLegacySupportControlStatus {
+    // Unavoidable because the register has many fields.
+    #[allow(clippy::too_many_lines)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UsbLegacySupportControlStatus")
+            .field("usb_smi_enable", &self.usb_smi_enable())
+            .field(
+                "smi_on_host_system_error_enable",
+                &self.smi_on_host_system_error_enable(),
+            )
+            .field(
+                "smi_on_os_ownership_enable",
+                &self.smi_on_os_ownership_enable(),
+            )
+            .field(
+                "smi_on_pci_command_enable",
+                &self.smi_on_pci_command_enable(),
+            )
+            .field("smi_on_bar_enable", &self.smi_on_bar_enable())
+            .field("smi_on_event_interrupt", &self.smi_on_event_interrupt())
+            .field("smi_on_host_system_error", &self.smi_on_host_system_error())
+            .field(
+                "smi_on_os_ownership_change",
+                &self.smi_on_os_ownership_change(),
+            )
+            .field("smi_on_pci_command", &self.smi_on_pci_command())
+            .field("smi_on_bar", &self.smi_on_bar())
+            .finish()
+    }
+}
@@ -0,0 +1,180 @@
+//! The identifiers of Device Slots, endpoints, and Streams.
+//!
+//! The raw values of these identifiers are easily mixed up, for example, an endpoint number is
+//! passed where a Device Context Index is expected. The types in this module are validated when
+//! they are created, and are taken and returned by the typed accessors of the TRBs, the Contexts,
+//! and the Doorbell Registers, such as `set_slot` and `endpoint` of the Command TRBs.
+//!
+//! # Examples
+//!
+//! ```
+//! use xhci::ids::{EndpointId, SlotId};
+//! use xhci::ring::trb::{command, transfer::Direction};
+//!
+//! let slot = SlotId::new(1).unwrap();
+//! let endpoint = EndpointId::from_endpoint(1, Direction::In).unwrap();
+//!
+//! let mut stop = command::StopEndpoint::new();
+//! stop.set_slot(slot).set_endpoint(endpoint);
+//!
+//! assert_eq!(stop.endpoint_id(), 3);
+//! assert_eq!(stop.endpoint(), Ok(endpoint));
+//! ```
+
+use crate::ring::trb::transfer::Direction;
+use core::convert::TryFrom;
+
+/// The ID of a Device Slot.
+///
+/// The value is in the range of 1..=255.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct SlotId(u8);
+impl SlotId {
+    /// Creates a new Slot ID.
+    ///
+    /// This method returns [`None`] if `id` is 0.
+    #[must_use]
+    pub const fn new(id: u8) -> Option<Self> {
+        if id == 0 {
+            None
+        } else {
+            Some(Self(id))
+        }
+    }
+
+    /// Returns the raw value.
+    #[must_use]
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+impl From<SlotId> for u8 {
+    fn from(id: SlotId) -> Self {
+        id.get()
+    }
+}
+impl From<SlotId> for usize {
+    fn from(id: SlotId) -> Self {
+        id.get().into()
+    }
+}
+impl TryFrom<u8> for SlotId {
+    type Error = u8;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        Self::new(id).ok_or(id)
+    }
+}
+
+/// The ID of an endpoint, that is, the Device Context Index (DCI).
+///
+/// The value is in the range of 1..=31. 1 is the Default Control Endpoint.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct EndpointId(u8);
+impl EndpointId {
+    /// The ID of the Default Control Endpoint.
+    pub const DEFAULT_CONTROL: Self = Self(1);
+
+    /// Creates a new Endpoint ID.
+    ///
+    /// This method returns [`None`] if `dci` is not in the range of 1..=31.
+    #[must_use]
+    pub const fn new(dci: u8) -> Option<Self> {
+        if dci >= 1 && dci <= 31 {
+            Some(Self(dci))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the ID of the endpoint with the endpoint number `number` and the direction
+    /// `direction`.
+    ///
+    /// The direction is ignored if `number` is 0. This method returns [`None`] if `number` is
+    /// larger than 15.
+    #[must_use]
+    pub fn from_endpoint(number: u8, direction: Direction) -> Option<Self> {
+        match number {
+            0 => Some(Self::DEFAULT_CONTROL),
+            1..=15 => Some(Self(number * 2 + u8::from(bool::from(direction)))),
+            _ => None,
+        }
+    }
+
+    /// Returns the endpoint number.
+    #[must_use]
+    pub const fn endpoint_number(self) -> u8 {
+        self.0 / 2
+    }
+
+    /// Returns the direction of the endpoint. This method returns [`None`] for the Default
+    /// Control Endpoint.
+    #[must_use]
+    pub fn direction(self) -> Option<Direction> {
+        if self == Self::DEFAULT_CONTROL {
+            None
+        } else {
+            Some((self.0 % 2 == 1).into())
+        }
+    }
+
+    /// Returns the raw value.
+    #[must_use]
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+impl From<EndpointId> for u8 {
+    fn from(id: EndpointId) -> Self {
+        id.get()
+    }
+}
+impl From<EndpointId> for usize {
+    fn from(id: EndpointId) -> Self {
+        id.get().into()
+    }
+}
+impl TryFrom<u8> for EndpointId {
+    type Error = u8;
+
+    fn try_from(dci: u8) -> Result<Self, Self::Error> {
+        Self::new(dci).ok_or(dci)
+    }
+}
+
+/// The ID of a Stream.
+///
+/// The value is in the range of 1..=65535. Stream ID 0 means that Streams are not used.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct StreamId(u16);
+impl StreamId {
+    /// Creates a new Stream ID.
+    ///
+    /// This method returns [`None`] if `id` is 0.
+    #[must_use]
+    pub const fn new(id: u16) -> Option<Self> {
+        if id == 0 {
+            None
+        } else {
+            Some(Self(id))
+        }
+    }
+
+    /// Returns the raw value.
+    #[must_use]
+    pub const fn get(self) -> u16 {
+        self.0
+    }
+}
+impl From<StreamId> for u16 {
+    fn from(id: StreamId) -> Self {
+        id.get()
+    }
+}
+impl TryFrom<u16> for StreamId {
+    type Error = u16;
+
+    fn try_from(id: u16) -> Result<Self, Self::Error> {
+        Self::new(id).ok_or(id)
+    }
+}
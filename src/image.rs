@@ -0,0 +1,170 @@
+//! Heap-allocated images of the MMIO space for tests on the host.
+//!
+//! This module is available with the `std` feature. [`Image`] is a zero-filled memory region in
+//! which the values of the registers are written by hand, and [`ImageMapper`] maps the addresses
+//! in the region to themselves. Passing [`Image::base`] as the MMIO base address allows parsing
+//! the registers and the Extended Capabilities with `cargo test`.
+//!
+//! # Examples
+//!
+//! ```
+//! use xhci::{extended_capabilities::List, image::Image, ExtendedCapability};
+//!
+//! let mut image = Image::new(0x1000);
+//!
+//! // The xHCI Extended Capabilities Pointer points to 0x500.
+//! image.write_u32(0x10, (0x500 >> 2) << 16);
+//! // A USB Legacy Support Capability without the next capability.
+//! image.write_u32(0x500, 1);
+//!
+//! let mut l = unsafe { List::from_mmio_base(image.base(), image.mapper()) }.unwrap();
+//! let mut it = l.into_iter();
+//!
+//! assert!(matches!(
+//!     it.next(),
+//!     Some(Ok(ExtendedCapability::UsbLegacySupportCapability(_)))
+//! ));
+//! assert!(it.next().is_none());
+//! ```
+
+use accessor::Mapper;
+use core::{convert::TryInto, num::NonZeroUsize, ptr};
+use std::alloc::{self, Layout};
+
+/// A zero-filled, 4096 byte aligned memory region on the heap.
+#[derive(Debug)]
+pub struct Image {
+    base: *mut u8,
+    layout: Layout,
+}
+impl Image {
+    /// Allocates an image of `len` bytes.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `len` is 0 or the allocation fails.
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        assert_ne!(len, 0, "The image must not be empty.");
+
+        let layout = Layout::from_size_align(len, 4096).unwrap();
+
+        // SAFETY: The size of `layout` is not 0.
+        let base = unsafe { alloc::alloc_zeroed(layout) };
+        if base.is_null() {
+            alloc::handle_alloc_error(layout);
+        }
+
+        Self { base, layout }
+    }
+
+    /// Returns the address of the image, which is used as the MMIO base address.
+    #[must_use]
+    pub fn base(&self) -> usize {
+        self.base as usize
+    }
+
+    /// Returns the length of the image in bytes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.layout.size()
+    }
+
+    /// Always returns `false` as an image is never empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns a mapper which maps the addresses in this image.
+    #[must_use]
+    pub fn mapper(&self) -> ImageMapper {
+        ImageMapper {
+            base: self.base(),
+            len: self.len(),
+        }
+    }
+
+    /// Reads the 32-bit value at `offset` bytes from the start of the image.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `offset` is not 4 byte aligned or the value is out of the image.
+    #[must_use]
+    pub fn read_u32(&self, offset: usize) -> u32 {
+        // SAFETY: `offset` is checked by `dword`.
+        unsafe { ptr::read_volatile(self.dword(offset)) }
+    }
+
+    /// Writes the 32-bit value `v` at `offset` bytes from the start of the image.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `offset` is not 4 byte aligned or the value is out of the image.
+    pub fn write_u32(&mut self, offset: usize, v: u32) {
+        // SAFETY: `offset` is checked by `dword`.
+        unsafe { ptr::write_volatile(self.dword(offset), v) }
+    }
+
+    /// Writes the 64-bit value `v` at `offset` bytes from the start of the image as two 32-bit
+    /// values, the lower one first.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `offset` is not 4 byte aligned or the value is out of the image.
+    pub fn write_u64(&mut self, offset: usize, v: u64) {
+        self.write_u32(offset, (v & 0xffff_ffff).try_into().unwrap());
+        self.write_u32(offset + 4, (v >> 32).try_into().unwrap());
+    }
+
+    fn dword(&self, offset: usize) -> *mut u32 {
+        assert!(
+            offset.trailing_zeros() >= 2,
+            "The offset must be 4 byte aligned."
+        );
+        assert!(
+            offset < self.len() && self.len() - offset >= 4,
+            "The offset is out of the image."
+        );
+
+        // SAFETY: `offset` is in the range of the allocated region.
+        unsafe { self.base.add(offset).cast() }
+    }
+}
+impl Drop for Image {
+    fn drop(&mut self) {
+        // SAFETY: `base` was allocated with `layout`.
+        unsafe { alloc::dealloc(self.base, self.layout) }
+    }
+}
+
+/// A [`Mapper`] which maps the addresses in an [`Image`] to themselves.
+///
+/// The image must outlive the accessors created with this mapper.
+#[derive(Copy, Clone, Debug)]
+pub struct ImageMapper {
+    base: usize,
+    len: usize,
+}
+impl Mapper for ImageMapper {
+    /// Returns `phys_start` as it is.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the region is out of the image. This means that the parsed value
+    /// points outside the MMIO space.
+    unsafe fn map(&mut self, phys_start: usize, bytes: usize) -> NonZeroUsize {
+        assert!(
+            phys_start >= self.base
+                && phys_start - self.base <= self.len
+                && bytes <= self.len - (phys_start - self.base),
+            "The region {:#x}..{:#x} is out of the image.",
+            phys_start,
+            phys_start.wrapping_add(bytes)
+        );
+
+        NonZeroUsize::new(phys_start).unwrap()
+    }
+
+    fn unmap(&mut self, _virt_start: usize, _bytes: usize) {}
+}
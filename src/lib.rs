@@ -33,6 +33,32 @@
 //! o.usbcmd.update(|u| u.set_run_stop(true));
 //! while o.usbsts.read().hc_halted() {}
 //! ```
+//!
+//! # The `strict` feature
+//!
+//! Enabling the `strict` feature adds runtime checks of some requirements of the xHCI
+//! specification which the xHC does not report clearly when violated:
+//!
+//! - [`Registers::ring_command_doorbell`] panics if the xHC is halted.
+//! - [`registers::runtime::Interrupter::set_interrupt_enable`] panics if interrupts are enabled
+//!   while the Event Handler Busy bit is set, that is, the Event Ring Dequeue Pointer Register has
+//!   not been updated after handling the events.
+//! - Converting a Normal, Data Stage, or Isoch TRB into a [`ring::trb::staging::Trb`] panics if
+//!   the data buffer crosses a 64 KB boundary. The check is done on the complete TRB, so the Data
+//!   Buffer Pointer and the TRB Transfer Length may be set in any order.
+//!
+//! The checks are compiled out if the feature is disabled.
+//!
+//! # Panicking setters
+//!
+//! The setters of the pointer fields panic if the pointer is not aligned correctly. Each of them
+//! has a `try_set_*` counterpart which returns [`Error::MisalignedPointer`] instead. Prefer the
+//! fallible ones where a panic is not acceptable, such as in a kernel.
+//!
+//! An xHC which supports only 32-bit addressing ignores the upper 32 bits of the pointers.
+//! [`Registers::try_set_dcbaap`], [`Registers::try_set_command_ring`], and
+//! [`registers::runtime::Interrupter::try_configure`] also check the pointers against the
+//! Addressing Capability bit and return [`Error::UnsupportedCapability`].
 
 #![no_std]
 #![deny(
@@ -59,11 +85,33 @@
     missing_debug_implementations
 )]
 
+#[cfg(feature = "std")]
+extern crate std;
+
+// Asserts a requirement of the xHCI specification only if the `strict` feature is enabled.
+macro_rules! strict_assert {
+    ($($arg:tt)*) => {
+        if cfg!(feature = "strict") {
+            assert!($($arg)*);
+        }
+    };
+}
+
 pub use accessor;
+pub use error::Error;
 pub use extended_capabilities::ExtendedCapability;
 pub use registers::Registers;
 
 pub mod context;
+#[cfg(feature = "emulator")]
+pub mod emulator;
+pub mod error;
 pub mod extended_capabilities;
+pub mod ids;
+#[cfg(feature = "std")]
+pub mod image;
+pub mod prelude;
+pub mod quirks;
 pub mod registers;
 pub mod ring;
+pub mod usb_req;
@@ -0,0 +1,31 @@
+//! The commonly used types and traits.
+//!
+//! The TRB modules are re-exported instead of their `Allowed` enums because the enums share the
+//! same name.
+//!
+//! # Examples
+//!
+//! ```
+//! use xhci::prelude::*;
+//!
+//! let mut input = context::byte32::Input::new();
+//! input.control_mut().set_aflag(0);
+//!
+//! let noop = command::Allowed::Noop(command::Noop::new());
+//! ```
+
+pub use crate::{
+    context::{
+        self, DeviceHandler, EndpointHandler, EndpointPairHandler, EndpointType,
+        InputControlHandler, InputHandler, SlotHandler,
+    },
+    error::{Error, RecoveryAction},
+    extended_capabilities::ExtendedCapability,
+    ids::{EndpointId, SlotId, StreamId},
+    registers::Registers,
+    ring::trb::{
+        command,
+        event::{self, CompletionCode, EventHandler},
+        transfer, Type,
+    },
+};
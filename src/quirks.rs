@@ -0,0 +1,125 @@
+//! The deviations of xHCs from the xHCI specification.
+//!
+//! Pass [`Quirks`] to [`crate::Registers::new_with_quirks`] to make the helper methods and the
+//! accessors of [`crate::Registers`] work around them.
+//!
+//! # Examples
+//!
+//! ```
+//! use xhci::quirks::Quirks;
+//!
+//! // Intel Lynx Point xHC.
+//! let q = Quirks::from_pci_ids(0x8086, 0x8c31);
+//!
+//! assert!(q.contains(Quirks::INTEL_PORT_MUX));
+//! assert!(q.contains(Quirks::RESET_DELAY));
+//! ```
+
+use core::ops::{BitOr, BitOrAssign};
+
+/// A set of the quirks of an xHC.
+#[derive(Copy, Clone, Default, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Quirks(u32);
+impl Quirks {
+    /// The USB ports are shared with an EHCI controller and routed to it by default.
+    ///
+    /// The ports must be switched to the xHC by copying the value at the offset 0xDC of the PCI
+    /// Configuration Space to the offset 0xD8 (the USB 3.0 ports), and the value at the offset
+    /// 0xD4 to the offset 0xD0 (the USB 2.0 ports). This crate does not access the PCI
+    /// Configuration Space, so the caller must do it before using the ports.
+    pub const INTEL_PORT_MUX: Self = Self(1 << 0);
+
+    /// The 64-bit registers must be written as two 32-bit writes, the upper one first.
+    ///
+    /// [`crate::registers::Register64`] writes the registers in this order. Pass the quirks to
+    /// [`crate::Registers::new_with_quirks`] and
+    /// [`crate::extended_capabilities::List::with_quirks`] to apply them.
+    pub const WRITE_64_HI_LO: Self = Self(1 << 1);
+
+    /// Accessing the registers right after setting the Host Controller Reset bit may hang the
+    /// system.
+    ///
+    /// [`crate::Registers::reset_controller`] calls the `delay` hook once before polling the
+    /// registers. The hook should wait for at least 1 ms.
+    pub const RESET_DELAY: Self = Self(1 << 2);
+
+    /// The xHC is emulated by a hypervisor, such as QEMU, and may set the reserved
+    /// bits of the Event TRBs.
+    ///
+    /// [`crate::ring::trb::event::Allowed::try_from_with_quirks`] ignores the reserved bits
+    /// instead of rejecting the TRBs.
+    ///
+    /// These xHCs may also clear the Controller Not Ready bit immediately after a reset. This
+    /// needs no workaround because [`crate::Registers::reset_controller`] and
+    /// [`crate::registers::operational::NotReady`] only wait for the bit to be cleared, and never
+    /// for it to be set.
+    pub const VIRTUAL_CONTROLLER: Self = Self(1 << 3);
+
+    /// Returns an empty set.
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns the known quirks of the xHC with the PCI Vendor ID `vendor_id` and the PCI Device
+    /// ID `device_id`.
+    ///
+    /// An empty set is returned if the xHC has no known quirk.
+    #[must_use]
+    pub fn from_pci_ids(vendor_id: u16, device_id: u16) -> Self {
+        const INTEL: u16 = 0x8086;
+        // Panther Point, Lynx Point, and Lynx Point-LP.
+        const INTEL_SWITCHABLE: [u16; 3] = [0x1e31, 0x8c31, 0x9c31];
+        const QEMU_XHCI: (u16, u16) = (0x1b36, 0x000d);
+
+        let mut q = Self::empty();
+
+        if (vendor_id, device_id) == QEMU_XHCI {
+            q |= Self::VIRTUAL_CONTROLLER;
+        }
+
+        if vendor_id == INTEL {
+            q |= Self::RESET_DELAY;
+
+            if INTEL_SWITCHABLE.contains(&device_id) {
+                q |= Self::INTEL_PORT_MUX;
+            }
+        }
+
+        q
+    }
+
+    /// Returns `true` if `self` contains all the quirks in `other`.
+    #[must_use]
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Adds the quirks in `other`.
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// Removes the quirks in `other`.
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+
+    /// Returns `true` if the set contains no quirk.
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+impl BitOr for Quirks {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+impl BitOrAssign for Quirks {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
@@ -1,8 +1,12 @@
 //! Host Controller Capability Registers
 
+use crate::Error;
 use accessor::Mapper;
 use bit_field::BitField;
-use core::{convert::TryInto, fmt};
+use core::{
+    convert::{TryFrom, TryInto},
+    fmt,
+};
 
 /// Host Controller Capability Registers
 #[derive(Debug)]
@@ -12,16 +16,24 @@ where
 {
     /// Capability Registers Length
     pub caplength: accessor::Single<CapabilityRegistersLength, M>,
+    /// Host Controller Interface Version Number
+    pub hciversion: accessor::Single<InterfaceVersionNumber, M>,
     /// Structural Parameters 1
     pub hcsparams1: accessor::Single<StructuralParameters1, M>,
     /// Structural Parameters 2
     pub hcsparams2: accessor::Single<StructuralParameters2, M>,
+    /// Structural Parameters 3
+    pub hcsparams3: accessor::Single<StructuralParameters3, M>,
     /// Capability Parameters 1
     pub hccparams1: accessor::Single<CapabilityParameters1, M>,
     /// Doorbell Offset
     pub dboff: accessor::Single<DoorbellOffset, M>,
     /// Runtime Register Space Offset
     pub rtsoff: accessor::Single<RuntimeRegisterSpaceOffset, M>,
+    /// Capability Parameters 2
+    pub hccparams2: accessor::Single<CapabilityParameters2, M>,
+    /// Virtualization Based Trusted IO Register Space Offset
+    pub vtiosoff: accessor::Single<VtioRegisterSpaceOffset, M>,
 }
 impl<M> Capability<M>
 where
@@ -49,13 +61,175 @@ where
 
         Self {
             caplength: m!(0x00),
+            hciversion: m!(0x02),
             hcsparams1: m!(0x04),
             hcsparams2: m!(0x08),
+            hcsparams3: m!(0x0c),
             hccparams1: m!(0x10),
             dboff: m!(0x14),
             rtsoff: m!(0x18),
+            hccparams2: m!(0x1c),
+            vtiosoff: m!(0x20),
         }
     }
+
+    /// Reads all the Capability Registers at once.
+    #[must_use]
+    pub fn snapshot(&self) -> CapabilitySnapshot {
+        CapabilitySnapshot {
+            caplength: self.caplength.read(),
+            hciversion: self.hciversion.read(),
+            hcsparams1: self.hcsparams1.read(),
+            hcsparams2: self.hcsparams2.read(),
+            hcsparams3: self.hcsparams3.read(),
+            hccparams1: self.hccparams1.read(),
+            dboff: self.dboff.read(),
+            rtsoff: self.rtsoff.read(),
+            hccparams2: self.hccparams2.read(),
+            vtiosoff: self.vtiosoff.read(),
+        }
+    }
+
+    /// Checks that the Capability Registers contain plausible values.
+    ///
+    /// `mmio_len` is the length of the MMIO region in bytes. This method checks that the
+    /// Operational Registers, the Doorbell Array, and the Runtime Registers including all the
+    /// Interrupter Register Sets are inside the region.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if any of the checks fails. This usually means that the MMIO
+    /// base address is wrong or not mapped.
+    pub fn validate(&self, mmio_len: usize) -> Result<(), InvalidCapability> {
+        self.validate_values()?;
+        self.validate_offsets(mmio_len)
+    }
+
+    fn validate_values(&self) -> Result<(), InvalidCapability> {
+        let caplength = self.caplength.read().get();
+        let version = self.hciversion.read().get();
+
+        if caplength < 0x20 {
+            Err(InvalidCapability::CapabilityRegistersLength(caplength))
+        } else if !(0x0090..=0x01ff).contains(&version) {
+            Err(InvalidCapability::InterfaceVersionNumber(version))
+        } else {
+            self.validate_structural_parameters()
+        }
+    }
+
+    fn validate_structural_parameters(&self) -> Result<(), InvalidCapability> {
+        let hcsparams1 = self.hcsparams1.read();
+
+        if hcsparams1.number_of_device_slots() == 0 {
+            Err(InvalidCapability::NoDeviceSlots)
+        } else if hcsparams1.number_of_ports() == 0 {
+            Err(InvalidCapability::NoPorts)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn validate_offsets(&self, mmio_len: usize) -> Result<(), InvalidCapability> {
+        let hcsparams1 = self.hcsparams1.read();
+        let ports = usize::from(hcsparams1.number_of_ports());
+        let slots = usize::from(hcsparams1.number_of_device_slots());
+        let interrupters = usize::from(hcsparams1.number_of_interrupts());
+
+        let operational = usize::from(self.caplength.read().get());
+        let doorbell = usize::try_from(self.dboff.read().get()).unwrap();
+        let runtime = usize::try_from(self.rtsoff.read().get()).unwrap();
+
+        let regions = [
+            (operational, 0x400 + ports * 0x10),
+            (doorbell, (slots + 1) * 4),
+            (runtime, 0x20 + interrupters * 0x20),
+        ];
+
+        match regions
+            .iter()
+            .find(|&&(offset, len)| offset > mmio_len || len > mmio_len - offset)
+        {
+            Some(&(offset, len)) => Err(InvalidCapability::OutOfRange { offset, len }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The values of the Host Controller Capability Registers read at once.
+///
+/// Unlike [`Capability`], reading the fields of this struct does not access the MMIO space.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use core::num::NonZeroUsize;
+/// # use xhci::accessor::Mapper;
+/// #
+/// # const MMIO_BASE: usize = 0x1000;
+/// #
+/// # #[derive(Clone)]
+/// # struct MemoryMapper;
+/// # impl Mapper for MemoryMapper {
+/// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+/// #         unimplemented!()
+/// #     }
+/// #
+/// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+/// #         unimplemented!()
+/// #     }
+/// # }
+/// #
+/// # let mapper = MemoryMapper;
+/// let r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+/// let c = r.capability.snapshot();
+///
+/// let ports = c.hcsparams1.number_of_ports();
+/// ```
+#[derive(Copy, Clone, Debug)]
+#[allow(clippy::module_name_repetitions)]
+pub struct CapabilitySnapshot {
+    /// Capability Registers Length
+    pub caplength: CapabilityRegistersLength,
+    /// Host Controller Interface Version Number
+    pub hciversion: InterfaceVersionNumber,
+    /// Structural Parameters 1
+    pub hcsparams1: StructuralParameters1,
+    /// Structural Parameters 2
+    pub hcsparams2: StructuralParameters2,
+    /// Structural Parameters 3
+    pub hcsparams3: StructuralParameters3,
+    /// Capability Parameters 1
+    pub hccparams1: CapabilityParameters1,
+    /// Doorbell Offset
+    pub dboff: DoorbellOffset,
+    /// Runtime Register Space Offset
+    pub rtsoff: RuntimeRegisterSpaceOffset,
+    /// Capability Parameters 2
+    pub hccparams2: CapabilityParameters2,
+    /// Virtualization Based Trusted IO Register Space Offset
+    pub vtiosoff: VtioRegisterSpaceOffset,
+}
+
+/// The errors returned when the Capability Registers contain invalid values.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum InvalidCapability {
+    /// The Capability Registers Length is less than 0x20.
+    CapabilityRegistersLength(u8),
+    /// The Host Controller Interface Version Number is not a known version.
+    InterfaceVersionNumber(u16),
+    /// The Number of Device Slots field is zero.
+    NoDeviceSlots,
+    /// The Number of Ports field is zero.
+    NoPorts,
+    /// The registers at the offset `offset` of `len` bytes are outside the MMIO region.
+    OutOfRange {
+        /// The offset of the registers from the MMIO base.
+        offset: usize,
+        /// The length of the registers in bytes.
+        len: usize,
+    },
 }
 
 /// Capability Registers Length
@@ -71,6 +245,18 @@ impl CapabilityRegistersLength {
     }
 }
 
+/// Host Controller Interface Version Number
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug)]
+pub struct InterfaceVersionNumber(u16);
+impl InterfaceVersionNumber {
+    /// Returns the version number in BCD. For example, xHCI 1.2 is `0x0120`.
+    #[must_use]
+    pub fn get(self) -> u16 {
+        self.0
+    }
+}
+
 /// Structural Parameters 1
 #[repr(transparent)]
 #[derive(Copy, Clone)]
@@ -82,6 +268,12 @@ impl StructuralParameters1 {
         self.0.get_bits(0..=7).try_into().unwrap()
     }
 
+    /// Returns the number of the interrupters.
+    #[must_use]
+    pub fn number_of_interrupts(self) -> u16 {
+        self.0.get_bits(8..=18).try_into().unwrap()
+    }
+
     /// Returns the number of ports.
     #[must_use]
     pub fn number_of_ports(self) -> u8 {
@@ -92,6 +284,7 @@ impl fmt::Debug for StructuralParameters1 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("StructuralParameters1")
             .field("number_of_device_slots", &self.number_of_device_slots())
+            .field("number_of_interrupts", &self.number_of_interrupts())
             .field("number_of_ports", &self.number_of_ports())
             .finish()
     }
@@ -102,6 +295,21 @@ impl fmt::Debug for StructuralParameters1 {
 #[derive(Copy, Clone)]
 pub struct StructuralParameters2(u32);
 impl StructuralParameters2 {
+    /// Returns the Isochronous Scheduling Threshold.
+    ///
+    /// This value indicates how far ahead of the current (micro)frame software must post
+    /// Isochronous TDs.
+    #[must_use]
+    pub fn isochronous_scheduling_threshold(self) -> IsochronousSchedulingThreshold {
+        let t = self.0.get_bits(0..=2).try_into().unwrap();
+
+        if self.0.get_bit(3) {
+            IsochronousSchedulingThreshold::Frames(t)
+        } else {
+            IsochronousSchedulingThreshold::Microframes(t)
+        }
+    }
+
     /// Returns the maximum number of the elements the Event Ring Segment Table can contain.
     ///
     /// Note that the `ERST Max` field of the Structural Parameters 2 register contains the exponential
@@ -111,7 +319,27 @@ impl StructuralParameters2 {
         2_u16.pow(self.erst_max())
     }
 
+    /// Creates a value of the Structural Parameters 2 register from a raw dword.
+    #[must_use]
+    pub const fn from_raw(raw: u32) -> Self {
+        Self(raw)
+    }
+
     /// Returns the number of scratchpads that xHC needs.
+    ///
+    /// The value is combined from the Max Scratchpad Bufs Hi and the Max Scratchpad Bufs Lo
+    /// fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::registers::capability::StructuralParameters2;
+    ///
+    /// // Max Scratchpad Bufs Hi is 1, and Max Scratchpad Bufs Lo is 2.
+    /// let p = StructuralParameters2::from_raw(0x1020_0000);
+    ///
+    /// assert_eq!(p.max_scratchpad_buffers(), 34);
+    /// ```
     #[must_use]
     pub fn max_scratchpad_buffers(self) -> u32 {
         let h = self.max_scratchpad_buffers_hi();
@@ -120,12 +348,21 @@ impl StructuralParameters2 {
         h << 5 | l
     }
 
+    /// Returns the value of the Scratchpad Restore field.
+    ///
+    /// If this is `true`, the xHC requires the integrity of the Scratchpad Buffer space to be
+    /// maintained across power events.
+    #[must_use]
+    pub fn scratchpad_restore(self) -> bool {
+        self.0.get_bit(26)
+    }
+
     fn erst_max(self) -> u32 {
         self.0.get_bits(4..=7)
     }
 
     fn max_scratchpad_buffers_hi(self) -> u32 {
-        self.0.get_bits(20..=25)
+        self.0.get_bits(21..=25)
     }
 
     fn max_scratchpad_buffers_lo(self) -> u32 {
@@ -135,11 +372,54 @@ impl StructuralParameters2 {
 impl fmt::Debug for StructuralParameters2 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("StructuralParameters2")
+            .field(
+                "isochronous_scheduling_threshold",
+                &self.isochronous_scheduling_threshold(),
+            )
             .field(
                 "event_ring_segment_table_max",
                 &self.event_ring_segment_table_max(),
             )
             .field("max_scratchpad_buffers", &self.max_scratchpad_buffers())
+            .field("scratchpad_restore", &self.scratchpad_restore())
+            .finish()
+    }
+}
+
+/// Isochronous Scheduling Threshold
+///
+/// The unit of the threshold is specified by the bit 3 of the IST field of the Structural
+/// Parameters 2 register.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum IsochronousSchedulingThreshold {
+    /// The threshold in microframes.
+    Microframes(u8),
+    /// The threshold in frames.
+    Frames(u8),
+}
+
+/// Structural Parameters 3
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct StructuralParameters3(u32);
+impl StructuralParameters3 {
+    /// Returns the value of the U1 Device Exit Latency field in microseconds.
+    #[must_use]
+    pub fn u1_device_exit_latency(self) -> u8 {
+        self.0.get_bits(0..=7).try_into().unwrap()
+    }
+
+    /// Returns the value of the U2 Device Exit Latency field in microseconds.
+    #[must_use]
+    pub fn u2_device_exit_latency(self) -> u16 {
+        self.0.get_bits(16..=31).try_into().unwrap()
+    }
+}
+impl fmt::Debug for StructuralParameters3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructuralParameters3")
+            .field("u1_device_exit_latency", &self.u1_device_exit_latency())
+            .field("u2_device_exit_latency", &self.u2_device_exit_latency())
             .finish()
     }
 }
@@ -150,6 +430,63 @@ impl fmt::Debug for StructuralParameters2 {
 #[allow(clippy::module_name_repetitions)]
 pub struct CapabilityParameters1(u32);
 impl CapabilityParameters1 {
+    /// Returns `true` if the xHC uses 64-bit address pointers.
+    #[must_use]
+    pub fn addressing_capability(self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// Checks whether the xHC can access the address `p`.
+    ///
+    /// An xHC which does not support 64-bit addressing ignores the upper 32 bits of the pointers
+    /// written to it. Call this method before writing a pointer to detect such a truncation.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::UnsupportedCapability`] if the xHC uses 32-bit address
+    /// pointers and `p` does not fit in 32 bits.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    ///
+    /// if r.capability.hccparams1.read().check_address(0x1_0000_0000).is_err() {
+    ///     // Allocate the memory below 4 GiB.
+    /// }
+    /// ```
+    pub fn check_address(self, p: u64) -> Result<(), Error> {
+        if self.addressing_capability() || p <= u32::MAX.into() {
+            Ok(())
+        } else {
+            Err(Error::UnsupportedCapability("64-bit addressing"))
+        }
+    }
+
+    /// Returns the value of the BW Negotiation Capability field.
+    #[must_use]
+    pub fn bw_negotiation_capability(self) -> bool {
+        self.0.get_bit(1)
+    }
+
     /// Returns `true` if the xHC uses 64 byte Context data structures, and `false` if the xHC uses
     /// 32 byte Context data structures.
     #[must_use]
@@ -157,6 +494,94 @@ impl CapabilityParameters1 {
         self.0.get_bit(2)
     }
 
+    /// Returns the value of the Port Power Control field.
+    #[must_use]
+    pub fn port_power_control(self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    /// Returns the value of the Port Indicators field.
+    #[must_use]
+    pub fn port_indicators(self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    /// Returns the value of the Light HC Reset Capability field.
+    #[must_use]
+    pub fn light_hc_reset_capability(self) -> bool {
+        self.0.get_bit(5)
+    }
+
+    /// Returns the value of the Latency Tolerance Messaging Capability field.
+    #[must_use]
+    pub fn latency_tolerance_messaging_capability(self) -> bool {
+        self.0.get_bit(6)
+    }
+
+    /// Returns the value of the No Secondary SID Support field.
+    #[must_use]
+    pub fn no_secondary_sid_support(self) -> bool {
+        self.0.get_bit(7)
+    }
+
+    /// Returns the value of the Parse All Event Data field.
+    #[must_use]
+    pub fn parse_all_event_data(self) -> bool {
+        self.0.get_bit(8)
+    }
+
+    /// Returns the value of the Stopped - Short Packet Capability field.
+    #[must_use]
+    pub fn stopped_short_packet_capability(self) -> bool {
+        self.0.get_bit(9)
+    }
+
+    /// Returns the value of the Stopped EDTLA Capability field.
+    #[must_use]
+    pub fn stopped_edtla_capability(self) -> bool {
+        self.0.get_bit(10)
+    }
+
+    /// Returns the value of the Contiguous Frame ID Capability field.
+    #[must_use]
+    pub fn contiguous_frame_id_capability(self) -> bool {
+        self.0.get_bit(11)
+    }
+
+    /// Returns the value of the Maximum Primary Stream Array Size field.
+    ///
+    /// Note that the maximum size of the Primary Stream Array is `2^(MaxPSASize+1)`.
+    #[must_use]
+    pub fn maximum_primary_stream_array_size(self) -> u8 {
+        self.0.get_bits(12..=15).try_into().unwrap()
+    }
+
+    /// Returns the maximum number of the entries of the Primary Stream Array, which is
+    /// `2^(MaxPSASize+1)`.
+    ///
+    /// This method returns 0 if the xHC does not support Streams.
+    #[must_use]
+    pub fn max_primary_streams(self) -> u32 {
+        match self.maximum_primary_stream_array_size() {
+            0 => 0,
+            s => 2_u32.pow(u32::from(s) + 1),
+        }
+    }
+
+    /// Returns the value of the Max Primary Streams field of the Endpoint Context for the Primary
+    /// Stream Array of `n` entries.
+    ///
+    /// This method returns [`None`] if `n` is not a power of two, is less than 4, or exceeds
+    /// [`CapabilityParameters1::max_primary_streams`].
+    #[must_use]
+    pub fn max_primary_streams_field_for(self, n: u32) -> Option<u8> {
+        if n.is_power_of_two() && n >= 4 && n <= self.max_primary_streams() {
+            Some((n.trailing_zeros() - 1).try_into().unwrap())
+        } else {
+            None
+        }
+    }
+
     /// Returns the offset of the xHCI extended capability list from the MMIO base. If this value is
     /// zero, the list does not exist.
     /// The base address can be calculated by `(MMIO base) + (xECP) << 2`
@@ -166,10 +591,45 @@ impl CapabilityParameters1 {
     }
 }
 impl fmt::Debug for CapabilityParameters1 {
+    // Unavoidable because the register has many fields.
+    #[allow(clippy::too_many_lines)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("CapabilityParameters1")
+            .field("addressing_capability", &self.addressing_capability())
+            .field(
+                "bw_negotiation_capability",
+                &self.bw_negotiation_capability(),
+            )
             .field("context_size", &self.context_size())
-            .field("xhci_extended_capabilities_pointer", &self.context_size())
+            .field("port_power_control", &self.port_power_control())
+            .field("port_indicators", &self.port_indicators())
+            .field(
+                "light_hc_reset_capability",
+                &self.light_hc_reset_capability(),
+            )
+            .field(
+                "latency_tolerance_messaging_capability",
+                &self.latency_tolerance_messaging_capability(),
+            )
+            .field("no_secondary_sid_support", &self.no_secondary_sid_support())
+            .field("parse_all_event_data", &self.parse_all_event_data())
+            .field(
+                "stopped_short_packet_capability",
+                &self.stopped_short_packet_capability(),
+            )
+            .field("stopped_edtla_capability", &self.stopped_edtla_capability())
+            .field(
+                "contiguous_frame_id_capability",
+                &self.contiguous_frame_id_capability(),
+            )
+            .field(
+                "maximum_primary_stream_array_size",
+                &self.maximum_primary_stream_array_size(),
+            )
+            .field(
+                "xhci_extended_capabilities_pointer",
+                &self.xhci_extended_capabilities_pointer(),
+            )
             .finish()
     }
 }
@@ -197,3 +657,130 @@ impl RuntimeRegisterSpaceOffset {
         self.0
     }
 }
+
+/// Virtualization Based Trusted IO Register Space Offset
+///
+/// This register is valid only if the Virtualization Based Trusted I/O Capability bit of the
+/// Capability Parameters 2 register is set.
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug)]
+pub struct VtioRegisterSpaceOffset(u32);
+impl VtioRegisterSpaceOffset {
+    /// Returns the offset of the VTIO Registers from the MMIO base.
+    #[must_use]
+    pub fn get(self) -> u32 {
+        self.0 & !0xfff
+    }
+}
+
+/// Capability Parameters 2
+///
+/// This register is defined since xHCI 1.1. The xHCs which conform to older versions return zero
+/// for all the fields.
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+#[allow(clippy::module_name_repetitions)]
+pub struct CapabilityParameters2(u32);
+impl CapabilityParameters2 {
+    /// Returns the value of the U3 Entry Capability field.
+    #[must_use]
+    pub fn u3_entry_capability(self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// Returns the value of the Configure Endpoint Command Max Exit Latency Too Large Capability field.
+    #[must_use]
+    pub fn configure_endpoint_command_max_exit_latency_too_large_capability(self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    /// Returns the value of the Force Save Context Capability field.
+    #[must_use]
+    pub fn force_save_context_capability(self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    /// Returns the value of the Compliance Transition Capability field.
+    #[must_use]
+    pub fn compliance_transition_capability(self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    /// Returns the value of the Large ESIT Payload Capability field.
+    #[must_use]
+    pub fn large_esit_payload_capability(self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    /// Returns the value of the Configuration Information Capability field.
+    #[must_use]
+    pub fn configuration_information_capability(self) -> bool {
+        self.0.get_bit(5)
+    }
+
+    /// Returns the value of the Extended TBC Capability field.
+    #[must_use]
+    pub fn extended_tbc_capability(self) -> bool {
+        self.0.get_bit(6)
+    }
+
+    /// Returns the value of the Extended TBC TRB Status Capability field.
+    #[must_use]
+    pub fn extended_tbc_trb_status_capability(self) -> bool {
+        self.0.get_bit(7)
+    }
+
+    /// Returns the value of the Get/Set Extended Property Capability field.
+    #[must_use]
+    pub fn get_set_extended_property_capability(self) -> bool {
+        self.0.get_bit(8)
+    }
+
+    /// Returns the value of the Virtualization Based Trusted I/O Capability field.
+    #[must_use]
+    pub fn virtualization_based_trusted_io_capability(self) -> bool {
+        self.0.get_bit(9)
+    }
+}
+impl fmt::Debug for CapabilityParameters2 {
+    // Unavoidable because the register has many fields.
+    #[allow(clippy::too_many_lines)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CapabilityParameters2")
+            .field("u3_entry_capability", &self.u3_entry_capability())
+            .field(
+                "configure_endpoint_command_max_exit_latency_too_large_capability",
+                &self.configure_endpoint_command_max_exit_latency_too_large_capability(),
+            )
+            .field(
+                "force_save_context_capability",
+                &self.force_save_context_capability(),
+            )
+            .field(
+                "compliance_transition_capability",
+                &self.compliance_transition_capability(),
+            )
+            .field(
+                "large_esit_payload_capability",
+                &self.large_esit_payload_capability(),
+            )
+            .field(
+                "configuration_information_capability",
+                &self.configuration_information_capability(),
+            )
+            .field("extended_tbc_capability", &self.extended_tbc_capability())
+            .field(
+                "extended_tbc_trb_status_capability",
+                &self.extended_tbc_trb_status_capability(),
+            )
+            .field(
+                "get_set_extended_property_capability",
+                &self.get_set_extended_property_capability(),
+            )
+            .field(
+                "virtualization_based_trusted_io_capability",
+                &self.virtualization_based_trusted_io_capability(),
+            )
+            .finish()
+    }
+}
@@ -1,20 +1,40 @@
 //! Doorbell Register
 
 use super::capability::Capability;
+use crate::{
+    ids::{EndpointId, SlotId, StreamId},
+    ring::trb::transfer::Direction,
+};
 use accessor::Mapper;
 use bit_field::BitField;
 use core::{
     convert::{TryFrom, TryInto},
     fmt,
+    sync::atomic::{self, Ordering},
 };
 
-/// The element of the Doorbell Array.
-#[repr(transparent)]
-#[derive(Copy, Clone)]
-pub struct Register(u32);
-impl Register {
+/// The Doorbell Array.
+///
+/// The Doorbell Registers always read as zero. This struct allows only writing them so that they
+/// are not updated with the read value.
+#[derive(Debug)]
+pub struct Array<M>
+where
+    M: Mapper + Clone,
+{
+    registers: accessor::Array<Register, M>,
+    base: usize,
+    mapper: M,
+}
+impl<M> Array<M>
+where
+    M: Mapper + Clone,
+{
     /// Creates a new accessor to the Doorbell Array.
     ///
+    /// The array contains the Doorbell Register 0 for the Host Controller and the ones for each
+    /// Device Slot.
+    ///
     /// # Safety
     ///
     /// Caller must ensure that the only one accessor is created, otherwise it may cause undefined
@@ -23,37 +43,374 @@ impl Register {
     /// # Panics
     ///
     /// This method panics if the base address of the Doorbell Array is not aligned correctly.
-    pub unsafe fn new<M1, M2>(
-        mmio_base: usize,
-        capability: &Capability<M2>,
-        mapper: M1,
-    ) -> accessor::Array<Self, M1>
+    pub unsafe fn new<M2>(mmio_base: usize, capability: &Capability<M2>, mapper: M) -> Self
     where
-        M1: Mapper,
         M2: Mapper + Clone,
     {
         let base = mmio_base + usize::try_from(capability.dboff.read().get()).unwrap();
-        accessor::Array::new(
+        let slots = usize::from(capability.hcsparams1.read().number_of_device_slots());
+
+        Self {
+            registers: accessor::Array::new(base, slots + 1, mapper.clone()),
             base,
-            capability.hcsparams1.read().number_of_device_slots().into(),
             mapper,
-        )
+        }
     }
-    /// Get a doorbell target.
+
+    /// Writes `r` to the `i`th Doorbell Register.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `i` is out of range.
+    pub fn write_at(&mut self, i: usize, r: Register) {
+        self.registers.write_at(i, r);
+    }
+
+    /// Returns the number of the Doorbell Registers.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.registers.len()
+    }
+
+    /// Returns `true` if there is no Doorbell Register.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Rings the doorbells of `doorbells` in order.
+    ///
+    /// This method issues a memory fence once before writing the Doorbell Registers so that the
+    /// xHC sees the TRBs placed on the rings before ringing the doorbells.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any of the Slot IDs is larger than the Number of Device Slots, or if
+    /// any of the targets is [`DoorbellTarget::HostControllerCommand`]. The doorbells before it
+    /// are rung in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// use xhci::ids::{EndpointId, SlotId};
+    /// use xhci::registers::doorbell::DoorbellTarget;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    ///
+    /// let slot1 = SlotId::new(1).unwrap();
+    /// let slot2 = SlotId::new(2).unwrap();
+    /// let ep3 = DoorbellTarget::from(EndpointId::new(3).unwrap());
+    ///
+    /// r.doorbell.ring_batch([(slot1, ep3), (slot2, ep3)].iter().copied());
+    /// ```
+    pub fn ring_batch<I>(&mut self, doorbells: I)
+    where
+        I: IntoIterator<Item = (SlotId, DoorbellTarget)>,
+    {
+        atomic::fence(Ordering::SeqCst);
+
+        for (slot, target) in doorbells {
+            assert_slot_target(target);
+            self.write_at(slot.into(), Register::new(target, None));
+        }
+    }
+
+    /// Returns a handle to the Doorbell Register of the Device Slot `slot`.
+    ///
+    /// The handle can be stored in the struct representing a device instead of the whole array.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `slot` is larger than the Number of Device Slots.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// use xhci::ids::{EndpointId, SlotId};
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    ///
+    /// let mut d = r.doorbell.slot_doorbell(SlotId::new(1).unwrap());
+    /// d.ring(EndpointId::DEFAULT_CONTROL.into(), None);
+    /// ```
+    #[must_use]
+    pub fn slot_doorbell(&self, slot: SlotId) -> SlotDoorbell<M> {
+        let i = usize::from(slot);
+        assert!(i < self.len(), "Slot ID out of range: {}", i);
+
+        // SAFETY: The Doorbell Registers are only written, and writing to a Doorbell Register does
+        // not affect the others.
+        let register = unsafe { accessor::Single::new(self.base + i * 4, self.mapper.clone()) };
+
+        SlotDoorbell { register, slot }
+    }
+}
+
+/// The Doorbell Register of a Device Slot.
+///
+/// This struct is created by [`Array::slot_doorbell`].
+#[derive(Debug)]
+pub struct SlotDoorbell<M>
+where
+    M: Mapper,
+{
+    register: accessor::Single<Register, M>,
+    slot: SlotId,
+}
+impl<M> SlotDoorbell<M>
+where
+    M: Mapper,
+{
+    /// Returns the ID of the Device Slot of this doorbell.
+    #[must_use]
+    pub fn slot_id(&self) -> SlotId {
+        self.slot
+    }
+
+    /// Rings the doorbell for `target` and `stream`.
+    ///
+    /// Pass [`None`] as `stream` unless the endpoint uses Streams.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `target` is [`DoorbellTarget::HostControllerCommand`], which is
+    /// reserved for the Doorbell Registers of the Device Slots.
+    pub fn ring(&mut self, target: DoorbellTarget, stream: Option<StreamId>) {
+        assert_slot_target(target);
+        self.register.write(Register::new(target, stream));
+    }
+}
+
+/// The element of the Doorbell Array.
+///
+/// The Doorbell Registers always read as zero. Write the whole value with [`Array::write_at`].
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct Register(u32);
+impl Register {
+    /// Creates a value of the Doorbell Register with the DB Target `target` and the DB Stream ID
+    /// `stream`. [`None`] as `stream` sets the DB Stream ID to 0.
+    #[must_use]
+    pub fn new(target: DoorbellTarget, stream: Option<StreamId>) -> Self {
+        let mut r = Self(0);
+        r.set_doorbell_target(target);
+        if let Some(s) = stream {
+            r.set_doorbell_stream_id(s.get());
+        }
+        r
+    }
+
+    /// Creates a value of the Doorbell Register from a raw dword.
+    ///
+    /// This is useful to compare the values captured from MMIO traces with the ones written by
+    /// this crate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::ids::EndpointId;
+    /// use xhci::registers::doorbell::{DoorbellTarget, Register};
+    ///
+    /// let r = Register::from_raw(0x0000_0003);
+    /// assert_eq!(
+    ///     r.doorbell_target(),
+    ///     Ok(DoorbellTarget::EpN(EndpointId::new(3).unwrap()))
+    /// );
+    /// assert_eq!(r.into_raw(), 0x0000_0003);
+    /// ```
+    #[must_use]
+    pub const fn from_raw(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the wrapped dword.
     #[must_use]
-    pub fn doorbell_target(self) -> u8 {
-        self.0.get_bits(0..=7).try_into().unwrap()
+    pub const fn into_raw(self) -> u32 {
+        self.0
+    }
+
+    /// Get a doorbell target.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the raw value as an error if it is reserved.
+    pub fn doorbell_target(self) -> Result<DoorbellTarget, u8> {
+        let t: u8 = self.0.get_bits(0..=7).try_into().unwrap();
+        DoorbellTarget::try_from(t)
     }
 
     /// Set a doorbell target.
-    pub fn set_doorbell_target(&mut self, target: u8) {
-        self.0.set_bits(0..=7, target.into());
+    pub fn set_doorbell_target(&mut self, target: DoorbellTarget) {
+        self.0.set_bits(0..=7, u8::from(target).into());
+    }
+
+    /// Returns the value of the DB Stream ID field.
+    #[must_use]
+    pub fn doorbell_stream_id(self) -> u16 {
+        self.0.get_bits(16..=31).try_into().unwrap()
+    }
+
+    /// Sets the value of the DB Stream ID field.
+    ///
+    /// Set the Stream ID to ring the doorbell of a Stream-enabled endpoint. Otherwise, leave it 0.
+    pub fn set_doorbell_stream_id(&mut self, id: u16) {
+        self.0.set_bits(16..=31, id.into());
     }
 }
 impl fmt::Debug for Register {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("doorbell::Register")
             .field("doorbell_target", &self.doorbell_target())
+            .field("doorbell_stream_id", &self.doorbell_stream_id())
             .finish()
     }
 }
+
+/// The value of the DB Target field.
+///
+/// The Host Controller Command target is valid only for the Doorbell Register 0, and the other
+/// targets are valid only for the Doorbell Registers of the Device Slots.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum DoorbellTarget {
+    /// Host Controller Command.
+    HostControllerCommand,
+    /// Control EP 0 Enqueue Pointer Update.
+    ControlEp0,
+    /// Enqueue Pointer Update of the endpoint.
+    ///
+    /// `EpN(EndpointId::DEFAULT_CONTROL)` is written as the same value as
+    /// [`DoorbellTarget::ControlEp0`], and is read back as the latter.
+    EpN(EndpointId),
+    /// Vendor Defined.
+    VendorDefined(VendorDefinedTarget),
+}
+impl DoorbellTarget {
+    /// Returns the target of the endpoint with the endpoint number `number` and the direction
+    /// `direction`.
+    ///
+    /// The direction is ignored if `number` is 0. This method returns [`None`] if `number` is
+    /// larger than 15.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::ids::EndpointId;
+    /// use xhci::registers::doorbell::DoorbellTarget;
+    /// use xhci::ring::trb::transfer::Direction;
+    ///
+    /// assert_eq!(
+    ///     DoorbellTarget::from_endpoint(0, Direction::In),
+    ///     Some(DoorbellTarget::ControlEp0)
+    /// );
+    /// assert_eq!(
+    ///     DoorbellTarget::from_endpoint(1, Direction::In),
+    ///     Some(DoorbellTarget::EpN(EndpointId::new(3).unwrap()))
+    /// );
+    /// assert_eq!(DoorbellTarget::from_endpoint(16, Direction::Out), None);
+    /// ```
+    #[must_use]
+    pub fn from_endpoint(number: u8, direction: Direction) -> Option<Self> {
+        EndpointId::from_endpoint(number, direction).map(Self::from)
+    }
+}
+impl From<EndpointId> for DoorbellTarget {
+    fn from(id: EndpointId) -> Self {
+        if id == EndpointId::DEFAULT_CONTROL {
+            Self::ControlEp0
+        } else {
+            Self::EpN(id)
+        }
+    }
+}
+impl From<DoorbellTarget> for u8 {
+    fn from(t: DoorbellTarget) -> Self {
+        match t {
+            DoorbellTarget::HostControllerCommand => 0,
+            DoorbellTarget::ControlEp0 => 1,
+            DoorbellTarget::EpN(id) => id.get(),
+            DoorbellTarget::VendorDefined(v) => v.get(),
+        }
+    }
+}
+impl TryFrom<u8> for DoorbellTarget {
+    type Error = u8;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(Self::HostControllerCommand),
+            1 => Ok(Self::ControlEp0),
+            _ => EndpointId::new(v)
+                .map(Self::EpN)
+                .or_else(|| VendorDefinedTarget::new(v).map(Self::VendorDefined))
+                .ok_or(v),
+        }
+    }
+}
+
+/// A Vendor Defined value of the DB Target field.
+///
+/// The value is in the range of 248..=255.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct VendorDefinedTarget(u8);
+impl VendorDefinedTarget {
+    /// Creates a new Vendor Defined target.
+    ///
+    /// This method returns [`None`] if `v` is not in the range of 248..=255.
+    #[must_use]
+    pub const fn new(v: u8) -> Option<Self> {
+        if v >= 248 {
+            Some(Self(v))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the raw value.
+    #[must_use]
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+// The DB Target 0 is reserved for the Doorbell Registers of the Device Slots.
+fn assert_slot_target(target: DoorbellTarget) {
+    assert_ne!(
+        target,
+        DoorbellTarget::HostControllerCommand,
+        "The Host Controller Command target is reserved for the Device Slots."
+    );
+}
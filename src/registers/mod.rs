@@ -1,15 +1,75 @@
 //! xHCI registers
+//!
+//! # Updating registers
+//!
+//! Each register is accessed through [`accessor::Single`] or [`accessor::Array`], except that the
+//! 64-bit registers are accessed through [`Register64`], which reads and writes them in the order
+//! the xHCI specification requires on 32-bit targets. Use their `update` and `update_at` methods
+//! to read a register, modify it with a closure, and write it back:
+//!
+//! ```no_run
+//! # use core::num::NonZeroUsize;
+//! # use xhci::accessor::Mapper;
+//! #
+//! # const MMIO_BASE: usize = 0x1000;
+//! #
+//! # #[derive(Clone)]
+//! # struct MemoryMapper;
+//! # impl Mapper for MemoryMapper {
+//! #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+//! #         unimplemented!()
+//! #     }
+//! #
+//! #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+//! #         unimplemented!()
+//! #     }
+//! # }
+//! #
+//! # let mapper = MemoryMapper;
+//! let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+//!
+//! r.operational.usbcmd.update(|u| u.set_run_stop(true));
+//! r.operational.config.update(|c| c.set_max_device_slots_enabled(8));
+//! ```
+//!
+//! Some registers must not be updated naively:
+//!
+//! - The USB Status Register and the Port Status and Control Register contain write-1-to-clear
+//!   bits. Writing back the read value clears all the bits which are set. Use the `clear_*` methods
+//!   of [`operational::UsbStatusRegister`] and
+//!   [`operational::PortStatusAndControlRegister::modify_preserving_changes`].
+//! - The Command Ring Pointer and the Ring Cycle State of the Command Ring Control Register read as
+//!   zero. Write the whole value created by [`operational::CommandRingControlRegister::new`].
+//! - The Interrupt Pending bit of the Interrupter Management Register and the Event Handler Busy
+//!   bit of the Event Ring Dequeue Pointer Register are write-1-to-clear bits. The setters of
+//!   [`runtime::InterrupterManagementRegister`] and [`runtime::EventRingDequeuePointerRegister`]
+//!   write 0 to these bits so that writing back the read value does not clear them.
+//! - The Doorbell Registers read as zero. [`doorbell::Array`] allows only writing them.
 
+use crate::{error, quirks::Quirks, Error};
 use accessor::Mapper;
+use core::{convert::TryInto, fmt};
+use doorbell::DoorbellTarget;
+use operational::{CommandRingControlRegister, PortStatusAndControlRegister, ResetTimeout};
+use runtime::{
+    EventRingDequeuePointerRegister, InterrupterConfiguration, InterrupterSnapshot,
+    MicroframeIndexRegister,
+};
 
-pub use capability::Capability;
-pub use operational::{Operational, PortRegisterSet};
-pub use runtime::InterruptRegisterSet;
+pub use capability::{Capability, CapabilitySnapshot, InvalidCapability};
+pub use operational::OperationalSnapshot;
+pub use operational::{
+    Operational, PortLinkState, PortRegisterSet, PortSet, Timeout, WritablePortLinkState,
+};
+pub use register64::Register64;
+pub use runtime::{InterruptRegisterSet, Interrupter, Runtime, RuntimeSnapshot};
 
 pub mod capability;
 pub mod doorbell;
 pub mod operational;
+mod register64;
 pub mod runtime;
+pub mod vtio;
 
 /// The access point to xHCI registers.
 #[derive(Debug)]
@@ -20,13 +80,16 @@ where
     /// Host Controller Capability Register
     pub capability: Capability<M>,
     /// Doorbell Array
-    pub doorbell: accessor::Array<doorbell::Register, M>,
+    pub doorbell: doorbell::Array<M>,
     /// Host Controller Operational Register
     pub operational: Operational<M>,
     /// Port Register Set Array
     pub port_register_set: accessor::Array<PortRegisterSet, M>,
-    /// Interrupt Register Set Array
-    pub interrupt_register_set: accessor::Array<InterruptRegisterSet, M>,
+    /// Host Controller Runtime Registers
+    pub runtime: Runtime<M>,
+    /// Interrupter Register Set Array
+    pub interrupt_register_set: InterruptRegisterSet<M>,
+    quirks: Quirks,
 }
 impl<M> Registers<M>
 where
@@ -69,18 +132,631 @@ where
     /// ```
     pub unsafe fn new(mmio_base: usize, mapper: M) -> Self {
         let capability = Capability::new(mmio_base, &mapper);
-        let doorbell = doorbell::Register::new(mmio_base, &capability, mapper.clone());
+        let doorbell = doorbell::Array::new(mmio_base, &capability, mapper.clone());
         let operational = Operational::new(mmio_base, capability.caplength.read(), &mapper);
         let port_register_set = PortRegisterSet::new(mmio_base, &capability, mapper.clone());
-        let interrupt_register_set =
-            InterruptRegisterSet::new(mmio_base, capability.rtsoff.read(), mapper);
+        let runtime = Runtime::new(mmio_base, capability.rtsoff.read(), &mapper);
+        let interrupt_register_set = InterruptRegisterSet::new(mmio_base, &capability, mapper);
 
         Self {
             capability,
             doorbell,
             operational,
             port_register_set,
+            runtime,
             interrupt_register_set,
+            quirks: Quirks::empty(),
         }
     }
+
+    /// Creates an instance of [`Registers`] whose helper methods and accessors to the 64-bit
+    /// registers work around `quirks`.
+    ///
+    /// Use [`Quirks::from_pci_ids`] to get the known quirks of the xHC.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the xHCI registers are accessed only through this struct.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `mmio_base` is not aligned correctly.
+    pub unsafe fn new_with_quirks(mmio_base: usize, mapper: M, quirks: Quirks) -> Self {
+        let r = Self::new(mmio_base, mapper);
+
+        Self {
+            operational: r.operational.with_quirks(quirks),
+            interrupt_register_set: r.interrupt_register_set.with_quirks(quirks),
+            quirks,
+            ..r
+        }
+    }
+
+    /// Returns the quirks which the helper methods work around.
+    #[must_use]
+    pub fn quirks(&self) -> Quirks {
+        self.quirks
+    }
+
+    /// Returns a handle to the Operational Registers which must be converted into
+    /// [`operational::Ready`] before writing them.
+    ///
+    /// See [`operational::NotReady`] for an example.
+    pub fn operational_mut(&mut self) -> operational::NotReady<'_, M> {
+        operational::NotReady::new(&mut self.operational)
+    }
+
+    /// Stops and resets the xHC.
+    ///
+    /// This method is the same as [`Operational::reset_controller`], except that it calls `delay`
+    /// once right after setting the Host Controller Reset bit if the quirks contain
+    /// [`Quirks::RESET_DELAY`]. In this case `delay` should wait for at least 1 ms.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error indicating the step which did not complete in time.
+    pub fn reset_controller<D>(&mut self, delay: D) -> Result<(), ResetTimeout>
+    where
+        D: FnMut() -> bool,
+    {
+        self.operational
+            .reset_controller_with_delay(delay, self.quirks.contains(Quirks::RESET_DELAY))
+    }
+
+    /// Creates an instance of [`Registers`] after checking the Capability Registers.
+    ///
+    /// `mmio_len` is the length of the MMIO region in bytes. See [`Capability::validate`] for the
+    /// performed checks.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the xHCI registers are accessed only through this struct.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the Capability Registers contain invalid values. This
+    /// usually means that `mmio_base` is wrong or not mapped.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `mmio_base` is not aligned correctly.
+    pub unsafe fn new_checked(
+        mmio_base: usize,
+        mmio_len: usize,
+        mapper: M,
+    ) -> Result<Self, InvalidCapability> {
+        Capability::new(mmio_base, &mapper).validate(mmio_len)?;
+
+        Ok(Self::new(mmio_base, mapper))
+    }
+
+    /// Puts all the enabled ports into the U3 state before the system enters a sleep state such
+    /// as S3.
+    ///
+    /// For each enabled port in U0, U1, or U2, this method writes U3 to the Port Link State field
+    /// with the Port Link State Write Strobe bit set, and sets the Wake on Connect Enable, Wake on
+    /// Disconnect Enable, and Wake on Over-current Enable bits. It then waits for all the ports to
+    /// enter U3 by polling the Port Link State fields. `delay` is called between the polls. It
+    /// should wait for a while and return `true`, or return `false` to give up waiting. The ports
+    /// are polled together, so `delay` decides the timeout of the whole operation. Linux waits for
+    /// up to 10 ms.
+    ///
+    /// Ports which are already in U3 and ports which are not enabled are left untouched.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`PortSet`] containing the indices of the enabled ports which did not
+    /// enter U3.
+    pub fn suspend_all_ports<D>(&mut self, mut delay: D) -> Result<(), PortSet>
+    where
+        D: FnMut() -> bool,
+    {
+        let mut suspending = PortSet::default();
+        let mut failed = PortSet::default();
+
+        for i in 0..self.port_register_set.len() {
+            match self.start_port_suspend(i) {
+                Some(true) => suspending.insert(i.try_into().unwrap()),
+                Some(false) => {}
+                None => failed.insert(i.try_into().unwrap()),
+            }
+        }
+
+        while self.ports_not_in_u3(suspending).next().is_some() && delay() {}
+
+        self.ports_not_in_u3(suspending)
+            .for_each(|i| failed.insert(i));
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(failed)
+        }
+    }
+
+    /// Resets the port with the index `i` and returns the Port Speed after the reset.
+    ///
+    /// This method sets the Port Reset bit and waits for the Port Reset Changed bit to be set. It
+    /// then clears the Port Reset Changed bit and the Warm Port Reset Change bit. `delay` is
+    /// called between the reads of the register. It should wait for a while and return `true`, or
+    /// return `false` to give up waiting.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `delay` returns `false` before the reset completes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// # fn sleep_us(us: u32) {}
+    /// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    /// # let port_index = 0;
+    ///
+    /// let mut tries = 0;
+    /// let speed = r.reset_port(port_index, || {
+    ///     sleep_us(1000);
+    ///     tries += 1;
+    ///     tries < 500
+    /// });
+    /// ```
+    pub fn reset_port<D>(&mut self, i: usize, delay: D) -> Result<u8, Timeout>
+    where
+        D: FnMut() -> bool,
+    {
+        self.port_register_set.update_at(i, |p| {
+            p.portsc
+                .modify_preserving_changes(|s| s.set_port_reset(true));
+        });
+
+        self.complete_port_reset(i, delay)
+    }
+
+    /// Warm-resets the USB3 port with the index `i` and returns the Port Speed after the reset.
+    ///
+    /// This method sets the Warm Port Reset bit instead of the Port Reset bit. See
+    /// [`Registers::reset_port`] for the rest.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `delay` returns `false` before the reset completes.
+    pub fn warm_reset_port<D>(&mut self, i: usize, delay: D) -> Result<u8, Timeout>
+    where
+        D: FnMut() -> bool,
+    {
+        self.port_register_set.update_at(i, |p| {
+            p.portsc
+                .modify_preserving_changes(PortStatusAndControlRegister::set_warm_port_reset);
+        });
+
+        self.complete_port_reset(i, delay)
+    }
+
+    /// Rings the Host Controller Command doorbell.
+    ///
+    /// Call this method after placing commands on the Command Ring.
+    ///
+    /// # Panics
+    ///
+    /// With the `strict` feature, this method panics if the xHC is halted.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    ///
+    /// // Place a command on the Command Ring, then:
+    /// r.ring_command_doorbell();
+    /// ```
+    pub fn ring_command_doorbell(&mut self) {
+        strict_assert!(
+            !self.operational.usbsts.read().hc_halted(),
+            "The doorbell must not be rung while the xHC is halted."
+        );
+        self.doorbell.write_at(
+            0,
+            doorbell::Register::new(DoorbellTarget::HostControllerCommand, None),
+        );
+    }
+
+    /// Sets the Device Context Base Address Array Pointer after checking it.
+    ///
+    /// Unlike [`operational::DeviceContextBaseAddressArrayPointerRegister::set`], this method
+    /// checks the Addressing Capability bit of the Capability Parameters 1 Register so that an xHC
+    /// which supports only 32-bit addressing does not silently truncate the pointer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::MisalignedPointer`] if `p` is not 64 byte aligned, and
+    /// [`Error::UnsupportedCapability`] if the xHC cannot access `p`. The register is not written
+    /// in these cases.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    /// # let dcbaa_addr = 0x1_0000_0000;
+    ///
+    /// if r.try_set_dcbaap(dcbaa_addr).is_err() {
+    ///     // Allocate the array below 4 GiB.
+    /// }
+    /// ```
+    pub fn try_set_dcbaap(&mut self, p: u64) -> Result<(), Error> {
+        self.check_pointer(p, 64)?;
+        self.operational.dcbaap.update(|d| d.set(p));
+        Ok(())
+    }
+
+    /// Writes the Command Ring Pointer and the Ring Cycle State to the Command Ring Control
+    /// Register after checking the pointer.
+    ///
+    /// See [`Registers::try_set_dcbaap`] for the performed checks.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::MisalignedPointer`] if `p` is not 64 byte aligned, and
+    /// [`Error::UnsupportedCapability`] if the xHC cannot access `p`. The register is not written
+    /// in these cases.
+    pub fn try_set_command_ring(&mut self, p: u64, cycle_state: bool) -> Result<(), Error> {
+        self.check_pointer(p, 64)?;
+        self.operational
+            .crcr
+            .write(CommandRingControlRegister::new(p, cycle_state));
+        Ok(())
+    }
+
+    /// Configures the interrupter with the index `index` like [`runtime::Interrupter::configure`]
+    /// after checking the pointers.
+    ///
+    /// See [`Registers::try_set_dcbaap`] for the performed checks.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::IndexOutOfRange`] if there is no such interrupter,
+    /// [`Error::MisalignedPointer`] if `c.erst_base` is not 64 byte aligned or `c.dequeue_pointer`
+    /// is not 16 byte aligned, and [`Error::UnsupportedCapability`] if the xHC cannot access them.
+    /// No register is written in these cases.
+    pub fn configure_interrupter(
+        &mut self,
+        index: usize,
+        c: &InterrupterConfiguration,
+    ) -> Result<(), Error> {
+        let len = self.interrupt_register_set.len();
+        if index >= len {
+            return Err(Error::IndexOutOfRange { index, len });
+        }
+
+        self.check_pointer(c.erst_base, 64)?;
+        self.check_pointer(c.dequeue_pointer, 16)?;
+
+        self.interrupt_register_set.interrupter(index).configure(c);
+        Ok(())
+    }
+
+    /// Updates the Event Ring Dequeue Pointer Register of the interrupter with the index `index`.
+    ///
+    /// The register is read, passed to `f`, and written back through [`Register64`]. The Event
+    /// Handler Busy bit is set to 0 before calling `f`, so the bit is cleared only if `f` calls
+    /// [`EventRingDequeuePointerRegister::clear_event_handler_busy`].
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::IndexOutOfRange`] if there is no such interrupter. The
+    /// register is not accessed in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    /// # let next_event = 0x2010;
+    ///
+    /// r.update_event_ring_dequeue_pointer(0, |e| e.set_event_ring_dequeue_pointer(next_event))
+    ///     .unwrap();
+    /// ```
+    pub fn update_event_ring_dequeue_pointer<U>(&mut self, index: usize, f: U) -> Result<(), Error>
+    where
+        U: FnOnce(&mut EventRingDequeuePointerRegister),
+    {
+        let len = self.interrupt_register_set.len();
+        if index >= len {
+            return Err(Error::IndexOutOfRange { index, len });
+        }
+
+        self.interrupt_register_set
+            .interrupter(index)
+            .erdp
+            .update(|e| {
+                e.keep_event_handler_busy();
+                f(e);
+            });
+        Ok(())
+    }
+
+    /// Reads the Host Controller Runtime Registers, including at most `N` Interrupter Register
+    /// Sets.
+    ///
+    /// The returned value is useful to print the state of the interrupters for debugging. It does
+    /// not borrow `self`, so it can be kept and compared with a later snapshot.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    /// let s = r.runtime_snapshot::<4>();
+    ///
+    /// for (i, interrupter) in s.interrupters().enumerate() {
+    ///     if interrupter.iman.interrupt_pending() {
+    ///         // Interrupter `i` has a pending interrupt.
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn runtime_snapshot<const N: usize>(&self) -> RuntimeSnapshot<N> {
+        RuntimeSnapshot::new(&self.runtime, &self.interrupt_register_set)
+    }
+
+    /// Reads the Capability, Operational, and Runtime Registers, the Port Register Sets, and the
+    /// Interrupter Register Sets at once.
+    ///
+    /// At most `PORTS` Port Register Sets and `INTERRUPTERS` Interrupter Register Sets are read.
+    /// The returned value does not borrow `self`, so it can be kept and printed later, for example,
+    /// to attach it to a bug report.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// use xhci::registers::Dump;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    /// let d: Dump<16, 4> = r.dump();
+    ///
+    /// // Print the registers with their field names.
+    /// println!("{:#?}", d);
+    /// ```
+    #[must_use]
+    pub fn dump<const PORTS: usize, const INTERRUPTERS: usize>(&self) -> Dump<PORTS, INTERRUPTERS> {
+        Dump {
+            capability: self.capability.snapshot(),
+            operational: self.operational.snapshot(),
+            mfindex: self.runtime.mfindex.read(),
+            ports: self.read_ports(),
+            interrupters: self.interrupt_register_set.read_snapshots(),
+        }
+    }
+
+    fn read_ports<const N: usize>(&self) -> [Option<PortRegisterSet>; N] {
+        let mut ports = [None; N];
+        for (i, p) in ports
+            .iter_mut()
+            .enumerate()
+            .take(self.port_register_set.len())
+        {
+            *p = Some(self.port_register_set.read_at(i));
+        }
+        ports
+    }
+
+    fn check_pointer(&self, p: u64, alignment: u64) -> Result<(), Error> {
+        error::check_alignment(p, alignment)?;
+        self.capability.hccparams1.read().check_address(p)
+    }
+
+    // The xHC sets the Port Reset Changed bit on the completion of both the Hot and the Warm
+    // Reset.
+    fn complete_port_reset<D>(&mut self, i: usize, mut delay: D) -> Result<u8, Timeout>
+    where
+        D: FnMut() -> bool,
+    {
+        while !self
+            .port_register_set
+            .read_at(i)
+            .portsc
+            .port_reset_changed()
+        {
+            if !delay() {
+                return Err(Timeout);
+            }
+        }
+
+        self.port_register_set.update_at(i, |p| {
+            p.portsc.modify_preserving_changes(|s| {
+                s.clear_port_reset_changed();
+                s.clear_warm_port_reset_change();
+            });
+        });
+
+        Ok(self.port_register_set.read_at(i).portsc.port_speed())
+    }
+
+    // Starts putting the port `i` into U3. Returns `Some(true)` if the port is entering U3,
+    // `Some(false)` if the port is already in U3 or not enabled, and `None` if the port cannot be
+    // suspended.
+    fn start_port_suspend(&mut self, i: usize) -> Option<bool> {
+        let portsc = self.port_register_set.read_at(i).portsc;
+        match portsc.port_link_state() {
+            _ if !portsc.port_enabled_disabled() => return Some(false),
+            Ok(PortLinkState::U3) => return Some(false),
+            Ok(PortLinkState::U0 | PortLinkState::U1 | PortLinkState::U2) => {}
+            _ => return None,
+        }
+
+        self.port_register_set.update_at(i, |p| {
+            p.portsc.modify_preserving_changes(|s| {
+                s.set_port_link_state(WritablePortLinkState::U3);
+                s.set_wake_on_connect_enable(true);
+                s.set_wake_on_disconnect_enable(true);
+                s.set_wake_on_over_current_enable(true);
+            });
+        });
+
+        Some(true)
+    }
+
+    fn ports_not_in_u3(&self, ports: PortSet) -> impl Iterator<Item = u8> + '_ {
+        (0..=u8::MAX).filter(move |&i| {
+            ports.contains(i)
+                && self
+                    .port_register_set
+                    .read_at(i.into())
+                    .portsc
+                    .port_link_state()
+                    != Ok(PortLinkState::U3)
+        })
+    }
+}
+
+/// The values of the xHCI registers read by [`Registers::dump`].
+///
+/// Formatting this struct with `{:#?}` prints all the registers with their field names.
+#[derive(Copy, Clone)]
+pub struct Dump<const PORTS: usize, const INTERRUPTERS: usize> {
+    /// Host Controller Capability Registers
+    pub capability: CapabilitySnapshot,
+    /// Host Controller Operational Registers
+    pub operational: OperationalSnapshot,
+    /// Microframe Index Register
+    pub mfindex: MicroframeIndexRegister,
+    ports: [Option<PortRegisterSet>; PORTS],
+    interrupters: [Option<InterrupterSnapshot>; INTERRUPTERS],
+}
+impl<const PORTS: usize, const INTERRUPTERS: usize> Dump<PORTS, INTERRUPTERS> {
+    /// Returns an iterator over the read Port Register Sets.
+    pub fn ports(&self) -> impl Iterator<Item = &PortRegisterSet> {
+        self.ports.iter().flatten()
+    }
+
+    /// Returns an iterator over the read Interrupter Register Sets.
+    pub fn interrupters(&self) -> impl Iterator<Item = &InterrupterSnapshot> {
+        self.interrupters.iter().flatten()
+    }
+}
+impl<const PORTS: usize, const INTERRUPTERS: usize> fmt::Debug for Dump<PORTS, INTERRUPTERS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dump")
+            .field("capability", &self.capability)
+            .field("operational", &self.operational)
+            .field("mfindex", &self.mfindex)
+            .field("ports", &List(|| self.ports()))
+            .field("interrupters", &List(|| self.interrupters()))
+            .finish()
+    }
+}
+
+// Formats the items returned by the iterator as a list.
+struct List<F>(F);
+impl<F, I> fmt::Debug for List<F>
+where
+    F: Fn() -> I,
+    I: Iterator,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries((self.0)()).finish()
+    }
 }
@@ -1,9 +1,19 @@
 //! Host Controller Operational Registers
 
-use super::capability::{Capability, CapabilityRegistersLength};
+use super::{
+    capability::{Capability, CapabilityRegistersLength},
+    Register64,
+};
+use crate::{error, quirks::Quirks, Error};
 use accessor::Mapper;
 use bit_field::BitField;
-use core::{convert::TryInto, fmt};
+use core::{
+    convert::TryInto,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
 
 /// Host Controller Operational Registers
 ///
@@ -20,9 +30,9 @@ where
     /// Page Size Register
     pub pagesize: accessor::Single<PageSizeRegister, M>,
     /// Command Ring Control Register
-    pub crcr: accessor::Single<CommandRingControlRegister, M>,
+    pub crcr: Register64<CommandRingControlRegister, M>,
     /// Device Context Base Address Array Pointer Register
-    pub dcbaap: accessor::Single<DeviceContextBaseAddressArrayPointerRegister, M>,
+    pub dcbaap: Register64<DeviceContextBaseAddressArrayPointerRegister, M>,
     /// Configure Register
     pub config: accessor::Single<ConfigureRegister, M>,
 }
@@ -57,11 +67,448 @@ where
             usbcmd: m!(0x00),
             usbsts: m!(0x04),
             pagesize: m!(0x08),
-            crcr: m!(0x18),
-            dcbaap: m!(0x30),
+            crcr: Register64::new(base + 0x18, mapper.clone()),
+            dcbaap: Register64::new(base + 0x30, mapper.clone()),
             config: m!(0x38),
         }
     }
+
+    /// Makes the accessors to the 64-bit registers work around `quirks`.
+    ///
+    /// See [`Register64`] for the affected writes.
+    #[must_use]
+    pub fn with_quirks(self, quirks: Quirks) -> Self {
+        Self {
+            crcr: self.crcr.with_quirks(quirks),
+            dcbaap: self.dcbaap.with_quirks(quirks),
+            ..self
+        }
+    }
+
+    /// Stops and resets the xHC.
+    ///
+    /// This method clears the Run/Stop bit and waits for the xHC to halt. It then sets the Host
+    /// Controller Reset bit and waits for the bit and the Controller Not Ready bit to be cleared.
+    /// See [`Operational::wait_for`] for `delay`. The same `delay` is used for all the steps, so
+    /// it decides both the polling interval and the timeout. The xHCI specification requires the
+    /// xHC to halt within 16 ms, and Linux waits for up to 1 second for the reset.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error indicating the step which did not complete in time.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// # fn sleep_us(us: u32) {}
+    /// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    ///
+    /// // Poll every 1 ms, and give up after 1 second.
+    /// let mut tries = 0;
+    /// r.operational
+    ///     .reset_controller(|| {
+    ///         sleep_us(1000);
+    ///         tries += 1;
+    ///         tries < 1000
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn reset_controller<D>(&mut self, delay: D) -> Result<(), ResetTimeout>
+    where
+        D: FnMut() -> bool,
+    {
+        self.reset_controller_with_delay(delay, false)
+    }
+
+    // Same as `reset_controller`, but calls `delay` once more right after setting the Host
+    // Controller Reset bit if `delay_after_reset` is `true`.
+    pub(crate) fn reset_controller_with_delay<D>(
+        &mut self,
+        mut delay: D,
+        delay_after_reset: bool,
+    ) -> Result<(), ResetTimeout>
+    where
+        D: FnMut() -> bool,
+    {
+        self.usbcmd.update(|c| c.set_run_stop(false));
+        self.wait_until_halted(&mut delay)
+            .map_err(|_| ResetTimeout::Halt)?;
+
+        self.usbcmd.update(|c| c.set_host_controller_reset(true));
+        if delay_after_reset && !delay() {
+            return Err(ResetTimeout::Reset);
+        }
+        while self.usbcmd.read().host_controller_reset() {
+            if !delay() {
+                return Err(ResetTimeout::Reset);
+            }
+        }
+
+        self.wait_until_ready(delay)
+            .map_err(|_| ResetTimeout::ControllerNotReady)
+    }
+
+    /// Waits until `cond` returns `true` for the value of the USB Status Register.
+    ///
+    /// `delay` is called between the reads of the register. It should wait for a while and return
+    /// `true`, or return `false` to give up waiting.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `delay` returns `false` before the condition is met.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// # fn sleep_us(us: u32) {}
+    /// let r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    ///
+    /// // Give up after 100 tries.
+    /// let mut tries = 0;
+    /// r.operational
+    ///     .wait_for(
+    ///         |s| s.port_change_detect(),
+    ///         || {
+    ///             sleep_us(1000);
+    ///             tries += 1;
+    ///             tries < 100
+    ///         },
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn wait_for<C, D>(&self, mut cond: C, mut delay: D) -> Result<(), Timeout>
+    where
+        C: FnMut(UsbStatusRegister) -> bool,
+        D: FnMut() -> bool,
+    {
+        while !cond(self.usbsts.read()) {
+            if !delay() {
+                return Err(Timeout);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::doc_markdown)]
+    /// Waits until the HCHalted bit is set.
+    ///
+    /// See [`Operational::wait_for`] for `delay`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `delay` returns `false` before the bit is set.
+    pub fn wait_until_halted<D>(&self, delay: D) -> Result<(), Timeout>
+    where
+        D: FnMut() -> bool,
+    {
+        self.wait_for(UsbStatusRegister::hc_halted, delay)
+    }
+
+    /// Waits until the Controller Not Ready bit is cleared.
+    ///
+    /// See [`Operational::wait_for`] for `delay`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `delay` returns `false` before the bit is cleared.
+    pub fn wait_until_ready<D>(&self, delay: D) -> Result<(), Timeout>
+    where
+        D: FnMut() -> bool,
+    {
+        self.wait_for(|s| !s.controller_not_ready(), delay)
+    }
+
+    /// Reads the USB Status Register once and summarizes the state of the xHC.
+    ///
+    /// See [`UsbStatusRegister::health`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// use xhci::registers::operational::Health;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    ///
+    /// if r.operational.health().is_fatal() {
+    ///     // Reset the xHC.
+    /// }
+    /// ```
+    #[must_use]
+    pub fn health(&self) -> Health {
+        self.usbsts.read().health()
+    }
+
+    /// Reads all the Host Controller Operational Registers at once.
+    ///
+    /// The Port Register Sets are not included.
+    #[must_use]
+    pub fn snapshot(&self) -> OperationalSnapshot {
+        OperationalSnapshot {
+            usbcmd: self.usbcmd.read(),
+            usbsts: self.usbsts.read(),
+            pagesize: self.pagesize.read(),
+            crcr: self.crcr.read(),
+            dcbaap: self.dcbaap.read(),
+            config: self.config.read(),
+        }
+    }
+}
+
+/// The values of the Host Controller Operational Registers read at once.
+///
+/// Unlike [`Operational`], reading the fields of this struct does not access the MMIO space.
+#[derive(Copy, Clone, Debug)]
+#[allow(clippy::module_name_repetitions)]
+pub struct OperationalSnapshot {
+    /// USB Command Register
+    pub usbcmd: UsbCommandRegister,
+    /// USB Status Register
+    pub usbsts: UsbStatusRegister,
+    /// Page Size Register
+    pub pagesize: PageSizeRegister,
+    /// Command Ring Control Register
+    pub crcr: CommandRingControlRegister,
+    /// Device Context Base Address Array Pointer Register
+    pub dcbaap: DeviceContextBaseAddressArrayPointerRegister,
+    /// Configure Register
+    pub config: ConfigureRegister,
+}
+
+/// The summarized state of the xHC returned by [`Operational::health`].
+///
+/// The variants are ordered from the most severe one.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Health {
+    /// The Host System Error bit is set. The xHC stopped because of a serious error, such as a
+    /// PCI bus error.
+    HostSystemError,
+    /// The Host Controller Error bit is set. The xHC stopped because of an internal error.
+    HostControllerError,
+    /// The Save/Restore Error bit is set.
+    SaveRestoreError,
+    /// The Controller Not Ready bit is set.
+    NotReady,
+    /// The xHC is halted.
+    Halted,
+    /// The xHC is running without errors.
+    Running,
+}
+impl Health {
+    /// Returns `true` if the xHC must be reset to recover.
+    #[must_use]
+    pub fn is_fatal(self) -> bool {
+        matches!(self, Self::HostSystemError | Self::HostControllerError)
+    }
+}
+
+/// A handle to the Host Controller Operational Registers which may not be written yet.
+///
+/// No Operational or Runtime Register may be written while the Controller Not Ready bit is set.
+/// This handle allows only reading the registers, and it must be converted into [`Ready`] by
+/// observing that the bit is cleared.
+///
+/// The check is advisory. [`crate::Registers::operational`] is a public field, so the registers
+/// can still be written without this handle. Get this handle with
+/// [`crate::Registers::operational_mut`], and do not write the field directly before the bit is
+/// observed to be cleared.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use core::num::NonZeroUsize;
+/// # use xhci::accessor::Mapper;
+/// #
+/// # const MMIO_BASE: usize = 0x1000;
+/// #
+/// # #[derive(Clone)]
+/// # struct MemoryMapper;
+/// # impl Mapper for MemoryMapper {
+/// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+/// #         unimplemented!()
+/// #     }
+/// #
+/// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+/// #         unimplemented!()
+/// #     }
+/// # }
+/// #
+/// # let mapper = MemoryMapper;
+/// # fn sleep_us(us: u32) {}
+/// # let dcbaa_addr = 0x1000;
+/// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+///
+/// let mut tries = 0;
+/// let mut o = r
+///     .operational_mut()
+///     .wait_until_ready(|| {
+///         sleep_us(1000);
+///         tries += 1;
+///         tries < 1000
+///     })
+///     .unwrap();
+///
+/// o.dcbaap.update(|d| d.set(dcbaa_addr));
+/// o.usbcmd.update(|c| c.set_run_stop(true));
+/// ```
+#[derive(Debug)]
+pub struct NotReady<'a, M>
+where
+    M: Mapper + Clone,
+{
+    operational: &'a mut Operational<M>,
+}
+impl<'a, M> NotReady<'a, M>
+where
+    M: Mapper + Clone,
+{
+    /// Creates a new handle to `operational`.
+    pub fn new(operational: &'a mut Operational<M>) -> Self {
+        Self { operational }
+    }
+
+    /// Reads the USB Status Register once, and converts this handle into [`Ready`] if the
+    /// Controller Not Ready bit is cleared.
+    ///
+    /// # Errors
+    ///
+    /// This method returns `self` if the bit is set.
+    pub fn try_into_ready(self) -> Result<Ready<'a, M>, Self> {
+        if self.operational.usbsts.read().controller_not_ready() {
+            Err(self)
+        } else {
+            Ok(Ready {
+                operational: self.operational,
+            })
+        }
+    }
+
+    /// Waits until the Controller Not Ready bit is cleared, and converts this handle into
+    /// [`Ready`].
+    ///
+    /// See [`Operational::wait_for`] for `delay`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `delay` returns `false` before the bit is cleared.
+    pub fn wait_until_ready<D>(self, delay: D) -> Result<Ready<'a, M>, Timeout>
+    where
+        D: FnMut() -> bool,
+    {
+        self.operational.wait_until_ready(delay)?;
+
+        Ok(Ready {
+            operational: self.operational,
+        })
+    }
+}
+impl<M> Deref for NotReady<'_, M>
+where
+    M: Mapper + Clone,
+{
+    type Target = Operational<M>;
+
+    fn deref(&self) -> &Self::Target {
+        self.operational
+    }
+}
+
+/// A handle to the Host Controller Operational Registers which are ready to be written.
+///
+/// This handle is created from [`NotReady`] after the Controller Not Ready bit is observed to be
+/// cleared. See [`NotReady`] for an example.
+#[derive(Debug)]
+pub struct Ready<'a, M>
+where
+    M: Mapper + Clone,
+{
+    operational: &'a mut Operational<M>,
+}
+impl<M> Deref for Ready<'_, M>
+where
+    M: Mapper + Clone,
+{
+    type Target = Operational<M>;
+
+    fn deref(&self) -> &Self::Target {
+        self.operational
+    }
+}
+impl<M> DerefMut for Ready<'_, M>
+where
+    M: Mapper + Clone,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.operational
+    }
+}
+
+/// The error returned when a condition is not met in time.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Timeout;
+
+/// The errors returned by [`Operational::reset_controller`].
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum ResetTimeout {
+    /// The xHC did not halt after the Run/Stop bit was cleared.
+    Halt,
+    /// The Host Controller Reset bit was not cleared.
+    Reset,
+    /// The Controller Not Ready bit was not cleared after the reset.
+    ControllerNotReady,
 }
 
 /// USB Command Register
@@ -90,17 +537,160 @@ impl UsbCommandRegister {
     pub fn set_host_controller_reset(&mut self, b: bool) {
         self.0.set_bit(1, b);
     }
+
+    /// Returns the value of the Interrupter Enable bit.
+    #[must_use]
+    pub fn interrupter_enable(self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    /// Sets the value of the Interrupter Enable bit.
+    pub fn set_interrupter_enable(&mut self, b: bool) {
+        self.0.set_bit(2, b);
+    }
+
+    /// Returns the value of the Host System Error Enable bit.
+    #[must_use]
+    pub fn host_system_error_enable(self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    /// Sets the value of the Host System Error Enable bit.
+    pub fn set_host_system_error_enable(&mut self, b: bool) {
+        self.0.set_bit(3, b);
+    }
+
+    /// Returns the value of the Light Host Controller Reset bit.
+    #[must_use]
+    pub fn light_host_controller_reset(self) -> bool {
+        self.0.get_bit(7)
+    }
+
+    /// Sets the value of the Light Host Controller Reset bit.
+    pub fn set_light_host_controller_reset(&mut self, b: bool) {
+        self.0.set_bit(7, b);
+    }
+
+    /// Returns the value of the Controller Save State bit.
+    #[must_use]
+    pub fn controller_save_state(self) -> bool {
+        self.0.get_bit(8)
+    }
+
+    /// Sets the value of the Controller Save State bit.
+    pub fn set_controller_save_state(&mut self, b: bool) {
+        self.0.set_bit(8, b);
+    }
+
+    /// Returns the value of the Controller Restore State bit.
+    #[must_use]
+    pub fn controller_restore_state(self) -> bool {
+        self.0.get_bit(9)
+    }
+
+    /// Sets the value of the Controller Restore State bit.
+    pub fn set_controller_restore_state(&mut self, b: bool) {
+        self.0.set_bit(9, b);
+    }
+
+    /// Returns the value of the Enable Wrap Event bit.
+    #[must_use]
+    pub fn enable_wrap_event(self) -> bool {
+        self.0.get_bit(10)
+    }
+
+    /// Sets the value of the Enable Wrap Event bit.
+    pub fn set_enable_wrap_event(&mut self, b: bool) {
+        self.0.set_bit(10, b);
+    }
+
+    /// Returns the value of the Enable U3 MFINDEX Stop bit.
+    #[must_use]
+    pub fn enable_u3_mfindex_stop(self) -> bool {
+        self.0.get_bit(11)
+    }
+
+    /// Sets the value of the Enable U3 MFINDEX Stop bit.
+    pub fn set_enable_u3_mfindex_stop(&mut self, b: bool) {
+        self.0.set_bit(11, b);
+    }
+
+    /// Returns the value of the CEM Enable bit.
+    #[must_use]
+    pub fn cem_enable(self) -> bool {
+        self.0.get_bit(13)
+    }
+
+    /// Sets the value of the CEM Enable bit.
+    pub fn set_cem_enable(&mut self, b: bool) {
+        self.0.set_bit(13, b);
+    }
+
+    /// Returns the value of the Extended TBC Enable bit.
+    #[must_use]
+    pub fn extended_tbc_enable(self) -> bool {
+        self.0.get_bit(14)
+    }
+
+    /// Sets the value of the Extended TBC Enable bit.
+    pub fn set_extended_tbc_enable(&mut self, b: bool) {
+        self.0.set_bit(14, b);
+    }
+
+    /// Returns the value of the Extended TBC TRB Status Enable bit.
+    #[must_use]
+    pub fn extended_tbc_trb_status_enable(self) -> bool {
+        self.0.get_bit(15)
+    }
+
+    /// Sets the value of the Extended TBC TRB Status Enable bit.
+    pub fn set_extended_tbc_trb_status_enable(&mut self, b: bool) {
+        self.0.set_bit(15, b);
+    }
+
+    /// Returns the value of the VTIO Enable bit.
+    #[must_use]
+    pub fn vtio_enable(self) -> bool {
+        self.0.get_bit(16)
+    }
+
+    /// Sets the value of the VTIO Enable bit.
+    pub fn set_vtio_enable(&mut self, b: bool) {
+        self.0.set_bit(16, b);
+    }
 }
 impl fmt::Debug for UsbCommandRegister {
+    // Unavoidable because the register has many fields.
+    #[allow(clippy::too_many_lines)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("UsbCommandRegister")
             .field("run_stop", &self.run_stop())
             .field("host_controller_reset", &self.host_controller_reset())
+            .field("interrupter_enable", &self.interrupter_enable())
+            .field("host_system_error_enable", &self.host_system_error_enable())
+            .field(
+                "light_host_controller_reset",
+                &self.light_host_controller_reset(),
+            )
+            .field("controller_save_state", &self.controller_save_state())
+            .field("controller_restore_state", &self.controller_restore_state())
+            .field("enable_wrap_event", &self.enable_wrap_event())
+            .field("enable_u3_mfindex_stop", &self.enable_u3_mfindex_stop())
+            .field("cem_enable", &self.cem_enable())
+            .field("extended_tbc_enable", &self.extended_tbc_enable())
+            .field(
+                "extended_tbc_trb_status_enable",
+                &self.extended_tbc_trb_status_enable(),
+            )
+            .field("vtio_enable", &self.vtio_enable())
             .finish()
     }
 }
 
 /// USB Status Register
+///
+/// Some bits of this register are write-1-to-clear. Writing back the read value clears all of them
+/// which are set. Use the `clear_*` methods to clear only one of them.
 #[repr(transparent)]
 #[derive(Copy, Clone)]
 pub struct UsbStatusRegister(u32);
@@ -118,6 +708,95 @@ impl UsbStatusRegister {
         self.0.get_bit(2)
     }
 
+    /// Clears the Host System Error bit.
+    ///
+    /// This method also prevents the other write-1-to-clear bits from being cleared by writing
+    /// the value back. See [`UsbStatusRegister::clear_event_interrupt`] for an example.
+    pub fn clear_host_system_error(&mut self) {
+        self.clear_write_one_bits_except(2);
+    }
+
+    /// Returns the value of the Event Interrupt bit.
+    #[must_use]
+    pub fn event_interrupt(self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    /// Clears the Event Interrupt bit.
+    ///
+    /// This method also prevents the other write-1-to-clear bits from being cleared by writing
+    /// the value back.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    ///
+    /// // The Port Change Detect bit is not cleared even if it is set.
+    /// r.operational.usbsts.update(|s| s.clear_event_interrupt());
+    /// ```
+    pub fn clear_event_interrupt(&mut self) {
+        self.clear_write_one_bits_except(3);
+    }
+
+    /// Returns the value of the Port Change Detect bit.
+    #[must_use]
+    pub fn port_change_detect(self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    /// Clears the Port Change Detect bit.
+    ///
+    /// This method also prevents the other write-1-to-clear bits from being cleared by writing
+    /// the value back. See [`UsbStatusRegister::clear_event_interrupt`] for an example.
+    pub fn clear_port_change_detect(&mut self) {
+        self.clear_write_one_bits_except(4);
+    }
+
+    /// Returns the value of the Save State Status bit.
+    #[must_use]
+    pub fn save_state_status(self) -> bool {
+        self.0.get_bit(8)
+    }
+
+    /// Returns the value of the Restore State Status bit.
+    #[must_use]
+    pub fn restore_state_status(self) -> bool {
+        self.0.get_bit(9)
+    }
+
+    /// Returns the value of the Save/Restore Error bit.
+    #[must_use]
+    pub fn save_restore_error(self) -> bool {
+        self.0.get_bit(10)
+    }
+
+    /// Clears the Save/Restore Error bit.
+    ///
+    /// This method also prevents the other write-1-to-clear bits from being cleared by writing
+    /// the value back. See [`UsbStatusRegister::clear_event_interrupt`] for an example.
+    pub fn clear_save_restore_error(&mut self) {
+        self.clear_write_one_bits_except(10);
+    }
+
     /// Returns the value of the Controller Not Ready bit.
     #[must_use]
     pub fn controller_not_ready(self) -> bool {
@@ -129,12 +808,45 @@ impl UsbStatusRegister {
     pub fn host_controller_error(self) -> bool {
         self.0.get_bit(12)
     }
+
+    /// Summarizes the state of the xHC.
+    ///
+    /// If more than one condition holds, the most severe one is returned. For example,
+    /// [`Health::HostSystemError`] is returned rather than [`Health::Halted`] because the xHC
+    /// halts on a Host System Error.
+    #[must_use]
+    pub fn health(self) -> Health {
+        if self.host_system_error() {
+            Health::HostSystemError
+        } else if self.host_controller_error() {
+            Health::HostControllerError
+        } else if self.save_restore_error() {
+            Health::SaveRestoreError
+        } else if self.controller_not_ready() {
+            Health::NotReady
+        } else if self.hc_halted() {
+            Health::Halted
+        } else {
+            Health::Running
+        }
+    }
+
+    fn clear_write_one_bits_except(&mut self, i: usize) {
+        for b in &[2, 3, 4, 10] {
+            self.0.set_bit(*b, *b == i);
+        }
+    }
 }
 impl fmt::Debug for UsbStatusRegister {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("UsbStatusRegister")
             .field("hc_halted", &self.hc_halted())
             .field("host_system_error", &self.host_system_error())
+            .field("event_interrupt", &self.event_interrupt())
+            .field("port_change_detect", &self.port_change_detect())
+            .field("save_state_status", &self.save_state_status())
+            .field("restore_state_status", &self.restore_state_status())
+            .field("save_restore_error", &self.save_restore_error())
             .field("controller_not_ready", &self.controller_not_ready())
             .field("host_controller_error", &self.host_controller_error())
             .finish()
@@ -151,18 +863,132 @@ impl PageSizeRegister {
     pub fn get(self) -> u16 {
         self.0.try_into().unwrap()
     }
+
+    /// Returns the smallest page size supported by the xHC in bytes.
+    ///
+    /// This method returns [`None`] if no bit is set.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    /// let p = r.operational.pagesize.read();
+    ///
+    /// let page_size = p.page_size_in_bytes();
+    /// for s in p.supported_page_sizes() {
+    ///     // `s` is in bytes, e.g. 4096.
+    /// }
+    /// ```
+    #[must_use]
+    pub fn page_size_in_bytes(self) -> Option<u32> {
+        self.supported_page_sizes().next()
+    }
+
+    /// Returns an iterator over the page sizes supported by the xHC in bytes, in ascending order.
+    pub fn supported_page_sizes(self) -> impl Iterator<Item = u32> {
+        let bits = self.0;
+        (0..16)
+            .filter(move |i| bits.get_bit(*i))
+            .map(|i| 1 << (i + 12))
+    }
 }
 
 /// Command Ring Controller Register
+///
+/// The Command Ring Pointer field and the Ring Cycle State bit always read as zero. Use
+/// [`CommandRingControlRegister::new`] to create the whole value to write instead of updating the
+/// read value.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use core::num::NonZeroUsize;
+/// # use xhci::accessor::Mapper;
+/// #
+/// # const MMIO_BASE: usize = 0x1000;
+/// #
+/// # #[derive(Clone)]
+/// # struct MemoryMapper;
+/// # impl Mapper for MemoryMapper {
+/// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+/// #         unimplemented!()
+/// #     }
+/// #
+/// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+/// #         unimplemented!()
+/// #     }
+/// # }
+/// #
+/// # let mapper = MemoryMapper;
+/// use xhci::registers::operational::CommandRingControlRegister;
+///
+/// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+/// # let ring_addr = 0x1000;
+///
+/// r.operational
+///     .crcr
+///     .write(CommandRingControlRegister::new(ring_addr, true));
+///
+/// // Stop the Command Ring. The xHC ignores the pointer while the ring is running.
+/// let mut c = CommandRingControlRegister::default();
+/// c.set_command_stop();
+/// r.operational.crcr.write(c);
+/// ```
 #[repr(transparent)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub struct CommandRingControlRegister(u64);
 impl CommandRingControlRegister {
+    /// Creates the value of the register with the Command Ring Pointer field and the Ring Cycle
+    /// State bit set.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `pointer` is not 64 byte aligned.
+    #[must_use]
+    pub fn new(pointer: u64, cycle_state: bool) -> Self {
+        let mut c = Self::default();
+        c.set_command_ring_pointer(pointer);
+        c.set_ring_cycle_state(cycle_state);
+        c
+    }
+
     /// Sets the value of the Ring Cycle State bit.
     pub fn set_ring_cycle_state(&mut self, s: bool) {
         self.0.set_bit(0, s);
     }
 
+    /// Sets the Command Stop bit.
+    ///
+    /// Writing this bit stops the Command Ring after the completion of the current command.
+    pub fn set_command_stop(&mut self) {
+        self.0.set_bit(1, true);
+    }
+
+    /// Sets the Command Abort bit.
+    ///
+    /// Writing this bit aborts the current command and stops the Command Ring.
+    pub fn set_command_abort(&mut self) {
+        self.0.set_bit(2, true);
+    }
+
     /// Returns the bit of the Command Ring Running bit.
     #[must_use]
     pub fn command_ring_running(self) -> bool {
@@ -173,13 +999,36 @@ impl CommandRingControlRegister {
     ///
     /// # Panics
     ///
-    /// This method panics if the given pointer is not 64 byte aligned.
+    /// This method panics if the given pointer is not 64 byte aligned. Use
+    /// [`CommandRingControlRegister::try_set_command_ring_pointer`] to handle the error.
     pub fn set_command_ring_pointer(&mut self, p: u64) {
         assert!(p.trailing_zeros() >= 6);
 
         let p = p >> 6;
         self.0.set_bits(6..=63, p);
     }
+
+    /// Sets the value of the Command Ring Pointer field without panicking.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::MisalignedPointer`] if the given pointer is not 64 byte
+    /// aligned. The field is not changed in this case.
+    pub fn try_set_command_ring_pointer(&mut self, p: u64) -> Result<(), Error> {
+        error::check_alignment(p, 64)?;
+        self.set_command_ring_pointer(p);
+        Ok(())
+    }
+}
+impl From<CommandRingControlRegister> for u64 {
+    fn from(c: CommandRingControlRegister) -> Self {
+        c.0
+    }
+}
+impl From<u64> for CommandRingControlRegister {
+    fn from(raw: u64) -> Self {
+        Self(raw)
+    }
 }
 impl fmt::Debug for CommandRingControlRegister {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -198,11 +1047,34 @@ impl DeviceContextBaseAddressArrayPointerRegister {
     ///
     /// # Panics
     ///
-    /// This method panics if the given pointer is not 64 byte aligned.
+    /// This method panics if the given pointer is not 64 byte aligned. Use
+    /// [`DeviceContextBaseAddressArrayPointerRegister::try_set`] to handle the error.
     pub fn set(&mut self, p: u64) {
         assert!(p.trailing_zeros() >= 6);
         self.0 = p;
     }
+
+    /// Sets the value of the Device Context Base Address Array Pointer without panicking.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::MisalignedPointer`] if the given pointer is not 64 byte
+    /// aligned. The register is not changed in this case.
+    pub fn try_set(&mut self, p: u64) -> Result<(), Error> {
+        error::check_alignment(p, 64)?;
+        self.set(p);
+        Ok(())
+    }
+}
+impl From<DeviceContextBaseAddressArrayPointerRegister> for u64 {
+    fn from(d: DeviceContextBaseAddressArrayPointerRegister) -> Self {
+        d.0
+    }
+}
+impl From<u64> for DeviceContextBaseAddressArrayPointerRegister {
+    fn from(raw: u64) -> Self {
+        Self(raw)
+    }
 }
 
 /// Configure Register
@@ -220,11 +1092,38 @@ impl ConfigureRegister {
     pub fn set_max_device_slots_enabled(&mut self, s: u8) {
         self.0.set_bits(0..=7, s.into());
     }
+
+    /// Returns the value of the U3 Entry Enable bit.
+    #[must_use]
+    pub fn u3_entry_enable(self) -> bool {
+        self.0.get_bit(8)
+    }
+
+    /// Sets the value of the U3 Entry Enable bit.
+    pub fn set_u3_entry_enable(&mut self, b: bool) {
+        self.0.set_bit(8, b);
+    }
+
+    /// Returns the value of the Configuration Information Enable bit.
+    #[must_use]
+    pub fn configuration_information_enable(self) -> bool {
+        self.0.get_bit(9)
+    }
+
+    /// Sets the value of the Configuration Information Enable bit.
+    pub fn set_configuration_information_enable(&mut self, b: bool) {
+        self.0.set_bit(9, b);
+    }
 }
 impl fmt::Debug for ConfigureRegister {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ConfigureRegister")
             .field("max_device_slots_enabled", &self.max_device_slots_enabled())
+            .field("u3_entry_enable", &self.u3_entry_enable())
+            .field(
+                "configuration_information_enable",
+                &self.configuration_information_enable(),
+            )
             .finish()
     }
 }
@@ -235,9 +1134,11 @@ impl fmt::Debug for ConfigureRegister {
 pub struct PortRegisterSet {
     /// Port Status and Control Register
     pub portsc: PortStatusAndControlRegister,
-    _portpmsc: u32,
+    /// Port Power Management Status and Control Register
+    pub portpmsc: PortPowerManagementStatusAndControlRegister,
     portli: u32,
-    porthlpmc: u32,
+    /// Port Hardware LPM Control Register
+    pub porthlpmc: PortHardwareLpmControlRegister,
 }
 impl PortRegisterSet {
     /// Creates a new accessor to the array of the Port Register Set.
@@ -269,6 +1170,10 @@ impl PortRegisterSet {
 }
 
 /// Port Status and Control Register
+///
+/// The Port Enabled/Disabled bit and the change bits are write-1-to-clear. Writing back the read
+/// value disables the port and clears the change bits which are set. Use
+/// [`PortStatusAndControlRegister::modify_preserving_changes`] to modify the value.
 #[repr(transparent)]
 #[derive(Copy, Clone)]
 pub struct PortStatusAndControlRegister(u32);
@@ -279,6 +1184,12 @@ impl PortStatusAndControlRegister {
         self.0.get_bit(0)
     }
 
+    /// Returns the value of the Port Enabled/Disabled bit.
+    #[must_use]
+    pub fn port_enabled_disabled(self) -> bool {
+        self.0.get_bit(1)
+    }
+
     /// Returns the value of the Port Reset bit.
     #[must_use]
     pub fn port_reset(self) -> bool {
@@ -290,25 +1201,562 @@ impl PortStatusAndControlRegister {
         self.0.set_bit(4, b);
     }
 
+    /// Returns the value of the Port Link State field.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an [`Err`] value with the Port Link State if it is reserved.
+    pub fn port_link_state(self) -> Result<PortLinkState, u8> {
+        let s: u8 = self.0.get_bits(5..=8).try_into().unwrap();
+        PortLinkState::from_u8(s).ok_or(s)
+    }
+
+    /// Sets the value of the Port Link State field and the Port Link State Write Strobe bit.
+    ///
+    /// The xHC ignores the written value unless the Port Link State Write Strobe bit is also set,
+    /// so this method sets both of them.
+    pub fn set_port_link_state(&mut self, s: WritablePortLinkState) {
+        self.0.set_bits(5..=8, s as u32);
+        self.set_port_link_state_write_strobe(true);
+    }
+
+    /// Returns the value of the Port Power bit.
+    #[must_use]
+    pub fn port_power(self) -> bool {
+        self.0.get_bit(9)
+    }
+
     /// Returns the value of the Port Speed field.
     #[must_use]
     pub fn port_speed(self) -> u8 {
         self.0.get_bits(10..=13).try_into().unwrap()
     }
 
+    /// Sets the value of the Port Link State Write Strobe bit.
+    pub fn set_port_link_state_write_strobe(&mut self, b: bool) {
+        self.0.set_bit(16, b);
+    }
+
+    /// Returns the value of the Warm Port Reset Change bit.
+    #[must_use]
+    pub fn warm_port_reset_change(self) -> bool {
+        self.0.get_bit(19)
+    }
+
+    /// Sets the Warm Port Reset Change bit to clear it.
+    pub fn clear_warm_port_reset_change(&mut self) {
+        self.0.set_bit(19, true);
+    }
+
     /// Returns the value of the Port Reset Changed bit.
     #[must_use]
     pub fn port_reset_changed(self) -> bool {
         self.0.get_bit(21)
     }
+
+    /// Sets the Port Reset Changed bit to clear it.
+    pub fn clear_port_reset_changed(&mut self) {
+        self.0.set_bit(21, true);
+    }
+
+    /// Returns the value of the Port Link State Change bit.
+    #[must_use]
+    pub fn port_link_state_change(self) -> bool {
+        self.0.get_bit(22)
+    }
+
+    /// Returns the value of the Wake on Connect Enable bit.
+    #[must_use]
+    pub fn wake_on_connect_enable(self) -> bool {
+        self.0.get_bit(25)
+    }
+
+    /// Sets the value of the Wake on Connect Enable bit.
+    pub fn set_wake_on_connect_enable(&mut self, b: bool) {
+        self.0.set_bit(25, b);
+    }
+
+    /// Returns the value of the Wake on Disconnect Enable bit.
+    #[must_use]
+    pub fn wake_on_disconnect_enable(self) -> bool {
+        self.0.get_bit(26)
+    }
+
+    /// Sets the value of the Wake on Disconnect Enable bit.
+    pub fn set_wake_on_disconnect_enable(&mut self, b: bool) {
+        self.0.set_bit(26, b);
+    }
+
+    /// Returns the value of the Wake on Over-current Enable bit.
+    #[must_use]
+    pub fn wake_on_over_current_enable(self) -> bool {
+        self.0.get_bit(27)
+    }
+
+    /// Sets the value of the Wake on Over-current Enable bit.
+    pub fn set_wake_on_over_current_enable(&mut self, b: bool) {
+        self.0.set_bit(27, b);
+    }
+
+    /// Modifies the value with `f` so that writing it back does not acknowledge the pending
+    /// changes, disable the port, or reset the port.
+    ///
+    /// This method clears the write-1-to-clear and write-1-to-set bits before calling `f`. The
+    /// bits which `f` sets are kept, so `f` can still clear a change bit or reset the port
+    /// explicitly.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    /// # let port_index = 0;
+    ///
+    /// r.port_register_set.update_at(port_index, |p| {
+    ///     p.portsc
+    ///         .modify_preserving_changes(|s| s.set_wake_on_connect_enable(true))
+    /// });
+    /// ```
+    pub fn modify_preserving_changes<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.clear_write_one_bits();
+        f(self);
+    }
+
+    /// Sets the Warm Port Reset bit to start a Warm Reset of the USB3 port.
+    pub fn set_warm_port_reset(&mut self) {
+        self.0.set_bit(31, true);
+    }
+
+    /// Clears the RW1C and RW1S bits so that writing back the value does not acknowledge the
+    /// pending changes, disable the port, or reset the port.
+    fn clear_write_one_bits(&mut self) {
+        self.0.set_bit(1, false);
+        self.0.set_bit(4, false);
+        self.0.set_bits(17..=23, 0);
+        self.0.set_bit(31, false);
+    }
 }
 impl fmt::Debug for PortStatusAndControlRegister {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("PortStatusAndControlRegister")
             .field("current_connect_status", &self.current_connect_status())
+            .field("port_enabled_disabled", &self.port_enabled_disabled())
             .field("port_reset", &self.port_reset())
+            .field("port_link_state", &self.port_link_state())
+            .field("port_power", &self.port_power())
             .field("port_speed", &self.port_speed())
+            .field("warm_port_reset_change", &self.warm_port_reset_change())
             .field("port_reset_changed", &self.port_reset_changed())
+            .field("port_link_state_change", &self.port_link_state_change())
+            .field("wake_on_connect_enable", &self.wake_on_connect_enable())
+            .field(
+                "wake_on_disconnect_enable",
+                &self.wake_on_disconnect_enable(),
+            )
+            .field(
+                "wake_on_over_current_enable",
+                &self.wake_on_over_current_enable(),
+            )
             .finish()
     }
 }
+
+/// Port Power Management Status and Control Register
+///
+/// The fields of this register depend on the protocol of the port. Use
+/// [`PortPowerManagementStatusAndControlRegister::usb2`] or
+/// [`PortPowerManagementStatusAndControlRegister::usb3`] to access the fields for a USB2 or a USB3
+/// port, respectively.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use core::num::NonZeroUsize;
+/// # use xhci::accessor::Mapper;
+/// #
+/// # const MMIO_BASE: usize = 0x1000;
+/// #
+/// # #[derive(Clone)]
+/// # struct MemoryMapper;
+/// # impl Mapper for MemoryMapper {
+/// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+/// #         unimplemented!()
+/// #     }
+/// #
+/// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+/// #         unimplemented!()
+/// #     }
+/// # }
+/// #
+/// # let mapper = MemoryMapper;
+/// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+/// # let port_index = 0;
+///
+/// r.port_register_set.update_at(port_index, |p| {
+///     let mut u = p.portpmsc.usb2();
+///     u.set_remote_wake_enable(true);
+///     p.portpmsc = u.into();
+/// });
+/// ```
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug)]
+pub struct PortPowerManagementStatusAndControlRegister(u32);
+impl PortPowerManagementStatusAndControlRegister {
+    /// Returns the value as the register of a USB2 port.
+    #[must_use]
+    pub fn usb2(self) -> Usb2PortPowerManagementStatusAndControlRegister {
+        Usb2PortPowerManagementStatusAndControlRegister(self.0)
+    }
+
+    /// Returns the value as the register of a USB3 port.
+    #[must_use]
+    pub fn usb3(self) -> Usb3PortPowerManagementStatusAndControlRegister {
+        Usb3PortPowerManagementStatusAndControlRegister(self.0)
+    }
+}
+impl From<Usb3PortPowerManagementStatusAndControlRegister>
+    for PortPowerManagementStatusAndControlRegister
+{
+    fn from(r: Usb3PortPowerManagementStatusAndControlRegister) -> Self {
+        Self(r.0)
+    }
+}
+impl From<Usb2PortPowerManagementStatusAndControlRegister>
+    for PortPowerManagementStatusAndControlRegister
+{
+    fn from(r: Usb2PortPowerManagementStatusAndControlRegister) -> Self {
+        Self(r.0)
+    }
+}
+
+/// Port Power Management Status and Control Register of a USB2 port.
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct Usb2PortPowerManagementStatusAndControlRegister(u32);
+impl Usb2PortPowerManagementStatusAndControlRegister {
+    /// Returns the value of the L1 Status field.
+    #[must_use]
+    pub fn l1_status(self) -> u8 {
+        self.0.get_bits(0..=2).try_into().unwrap()
+    }
+
+    /// Returns the value of the Remote Wake Enable bit.
+    #[must_use]
+    pub fn remote_wake_enable(self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    /// Sets the value of the Remote Wake Enable bit.
+    pub fn set_remote_wake_enable(&mut self, b: bool) {
+        self.0.set_bit(3, b);
+    }
+
+    /// Returns the value of the Best Effort Service Latency field.
+    #[must_use]
+    pub fn best_effort_service_latency(self) -> u8 {
+        self.0.get_bits(4..=7).try_into().unwrap()
+    }
+
+    /// Sets the value of the Best Effort Service Latency field.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `l > 15`.
+    pub fn set_best_effort_service_latency(&mut self, l: u8) {
+        assert!(
+            l <= 15,
+            "The Best Effort Service Latency must be less than 16."
+        );
+        self.0.set_bits(4..=7, l.into());
+    }
+
+    /// Returns the value of the L1 Device Slot field.
+    #[must_use]
+    pub fn l1_device_slot(self) -> u8 {
+        self.0.get_bits(8..=15).try_into().unwrap()
+    }
+
+    /// Sets the value of the L1 Device Slot field.
+    pub fn set_l1_device_slot(&mut self, s: u8) {
+        self.0.set_bits(8..=15, s.into());
+    }
+
+    /// Returns the value of the Hardware LPM Enable bit.
+    #[must_use]
+    pub fn hardware_lpm_enable(self) -> bool {
+        self.0.get_bit(16)
+    }
+
+    /// Sets the value of the Hardware LPM Enable bit.
+    pub fn set_hardware_lpm_enable(&mut self, b: bool) {
+        self.0.set_bit(16, b);
+    }
+
+    /// Returns the value of the Port Test Control field.
+    #[must_use]
+    pub fn port_test_control(self) -> u8 {
+        self.0.get_bits(28..=31).try_into().unwrap()
+    }
+
+    /// Sets the value of the Port Test Control field.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `c > 15`.
+    pub fn set_port_test_control(&mut self, c: u8) {
+        assert!(c <= 15, "The Port Test Control must be less than 16.");
+        self.0.set_bits(28..=31, c.into());
+    }
+}
+impl fmt::Debug for Usb2PortPowerManagementStatusAndControlRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Usb2PortPowerManagementStatusAndControlRegister")
+            .field("l1_status", &self.l1_status())
+            .field("remote_wake_enable", &self.remote_wake_enable())
+            .field(
+                "best_effort_service_latency",
+                &self.best_effort_service_latency(),
+            )
+            .field("l1_device_slot", &self.l1_device_slot())
+            .field("hardware_lpm_enable", &self.hardware_lpm_enable())
+            .field("port_test_control", &self.port_test_control())
+            .finish()
+    }
+}
+
+/// Port Power Management Status and Control Register of a USB3 port.
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct Usb3PortPowerManagementStatusAndControlRegister(u32);
+impl Usb3PortPowerManagementStatusAndControlRegister {
+    /// Returns the value of the U1 Timeout field.
+    #[must_use]
+    pub fn u1_timeout(self) -> u8 {
+        self.0.get_bits(0..=7).try_into().unwrap()
+    }
+
+    /// Sets the value of the U1 Timeout field.
+    pub fn set_u1_timeout(&mut self, t: u8) {
+        self.0.set_bits(0..=7, t.into());
+    }
+
+    /// Returns the value of the U2 Timeout field.
+    #[must_use]
+    pub fn u2_timeout(self) -> u8 {
+        self.0.get_bits(8..=15).try_into().unwrap()
+    }
+
+    /// Sets the value of the U2 Timeout field.
+    pub fn set_u2_timeout(&mut self, t: u8) {
+        self.0.set_bits(8..=15, t.into());
+    }
+
+    /// Returns the value of the Force Link PM Accept bit.
+    #[must_use]
+    pub fn force_link_pm_accept(self) -> bool {
+        self.0.get_bit(16)
+    }
+
+    /// Sets the value of the Force Link PM Accept bit.
+    pub fn set_force_link_pm_accept(&mut self, b: bool) {
+        self.0.set_bit(16, b);
+    }
+}
+impl fmt::Debug for Usb3PortPowerManagementStatusAndControlRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Usb3PortPowerManagementStatusAndControlRegister")
+            .field("u1_timeout", &self.u1_timeout())
+            .field("u2_timeout", &self.u2_timeout())
+            .field("force_link_pm_accept", &self.force_link_pm_accept())
+            .finish()
+    }
+}
+
+/// Port Hardware LPM Control Register
+///
+/// The fields of this register are defined only for USB2 ports.
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct PortHardwareLpmControlRegister(u32);
+impl PortHardwareLpmControlRegister {
+    /// Returns the value of the Host Initiated Resume Duration Mode field.
+    #[must_use]
+    pub fn host_initiated_resume_duration_mode(self) -> u8 {
+        self.0.get_bits(0..=1).try_into().unwrap()
+    }
+
+    /// Sets the value of the Host Initiated Resume Duration Mode field.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `m > 3`.
+    pub fn set_host_initiated_resume_duration_mode(&mut self, m: u8) {
+        assert!(
+            m <= 3,
+            "The Host Initiated Resume Duration Mode must be less than 4."
+        );
+        self.0.set_bits(0..=1, m.into());
+    }
+
+    /// Returns the value of the L1 Timeout field.
+    #[must_use]
+    pub fn l1_timeout(self) -> u8 {
+        self.0.get_bits(2..=9).try_into().unwrap()
+    }
+
+    /// Sets the value of the L1 Timeout field.
+    pub fn set_l1_timeout(&mut self, t: u8) {
+        self.0.set_bits(2..=9, t.into());
+    }
+
+    /// Returns the value of the Best Effort Service Latency Deep field.
+    #[must_use]
+    pub fn best_effort_service_latency_deep(self) -> u8 {
+        self.0.get_bits(10..=13).try_into().unwrap()
+    }
+
+    /// Sets the value of the Best Effort Service Latency Deep field.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `l > 15`.
+    pub fn set_best_effort_service_latency_deep(&mut self, l: u8) {
+        assert!(
+            l <= 15,
+            "The Best Effort Service Latency Deep must be less than 16."
+        );
+        self.0.set_bits(10..=13, l.into());
+    }
+}
+impl fmt::Debug for PortHardwareLpmControlRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PortHardwareLpmControlRegister")
+            .field(
+                "host_initiated_resume_duration_mode",
+                &self.host_initiated_resume_duration_mode(),
+            )
+            .field("l1_timeout", &self.l1_timeout())
+            .field(
+                "best_effort_service_latency_deep",
+                &self.best_effort_service_latency_deep(),
+            )
+            .finish()
+    }
+}
+
+/// The states of the Port Link State field.
+///
+/// The values are defined in the xHCI specification Section 5.4.8, Table 5-27.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, FromPrimitive)]
+pub enum PortLinkState {
+    /// U0 State.
+    U0 = 0,
+    /// U1 State.
+    U1 = 1,
+    /// U2 State.
+    U2 = 2,
+    /// U3 State (Device Suspended).
+    U3 = 3,
+    /// Disabled State.
+    Disabled = 4,
+    #[allow(clippy::doc_markdown)]
+    /// RxDetect State.
+    RxDetect = 5,
+    /// Inactive State.
+    Inactive = 6,
+    /// Polling State.
+    Polling = 7,
+    /// Recovery State.
+    Recovery = 8,
+    /// Hot Reset State.
+    HotReset = 9,
+    /// Compliance Mode State.
+    ComplianceMode = 10,
+    /// Test Mode State.
+    TestMode = 11,
+    /// Resume State.
+    Resume = 15,
+}
+
+/// The states which software may write to the Port Link State field.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum WritablePortLinkState {
+    /// Transits the port to U0.
+    U0 = 0,
+    /// Transits the USB2 port to U2.
+    U2 = 2,
+    /// Transits the port to U3.
+    U3 = 3,
+    /// Disables the USB3 port.
+    Disabled = 4,
+    #[allow(clippy::doc_markdown)]
+    /// Transits the disabled USB3 port to RxDetect.
+    RxDetect = 5,
+    /// Transits the USB3 port to Compliance Mode.
+    ComplianceMode = 10,
+    /// Resumes the suspended USB2 port.
+    Resume = 15,
+}
+impl From<WritablePortLinkState> for PortLinkState {
+    fn from(s: WritablePortLinkState) -> Self {
+        match s {
+            WritablePortLinkState::U0 => Self::U0,
+            WritablePortLinkState::U2 => Self::U2,
+            WritablePortLinkState::U3 => Self::U3,
+            WritablePortLinkState::Disabled => Self::Disabled,
+            WritablePortLinkState::RxDetect => Self::RxDetect,
+            WritablePortLinkState::ComplianceMode => Self::ComplianceMode,
+            WritablePortLinkState::Resume => Self::Resume,
+        }
+    }
+}
+
+/// A set of the indices of the Port Register Sets.
+///
+/// The index `i` corresponds to the Port Register Set `i` of the array, which is the port number
+/// `i + 1`.
+#[derive(Copy, Clone, Default, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct PortSet([u32; 8]);
+impl PortSet {
+    /// Returns `true` if the set contains the index `i`.
+    #[must_use]
+    pub fn contains(&self, i: u8) -> bool {
+        self.0[usize::from(i / 32)].get_bit(usize::from(i % 32))
+    }
+
+    /// Adds the index `i` to the set.
+    pub fn insert(&mut self, i: u8) {
+        self.0[usize::from(i / 32)].set_bit(usize::from(i % 32), true);
+    }
+
+    /// Returns `true` if the set contains no index.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|&x| x == 0)
+    }
+
+    /// Returns an iterator over the indices in the set in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..=u8::MAX).filter(move |&i| self.contains(i))
+    }
+}
@@ -0,0 +1,106 @@
+//! 64-bit registers.
+
+use crate::quirks::Quirks;
+use accessor::Mapper;
+use core::{convert::TryInto, fmt};
+
+/// An accessor to a 64-bit register.
+///
+/// A host which cannot access 64 bits at once must access a 64-bit register as two 32-bit
+/// accesses, the lower dword first. On 32-bit targets, the compiler splits a 64-bit volatile
+/// access in an unspecified order, so this accessor reads and writes the two dwords by itself.
+/// The two accesses are not atomic, so a value which the xHC changes between them may be torn.
+/// On the other targets, the register is read and written at once.
+///
+/// If the quirks contain [`Quirks::WRITE_64_HI_LO`], the upper dword is written first on all
+/// targets. Reads are not affected by the quirks.
+///
+/// The methods of this struct are the same as those of [`accessor::Single`].
+pub struct Register64<T, M>
+where
+    M: Mapper + Clone,
+{
+    whole: accessor::Single<T, M>,
+    dwords: accessor::Array<u32, M>,
+    quirks: Quirks,
+}
+impl<T, M> Register64<T, M>
+where
+    T: Copy + Into<u64> + From<u64>,
+    M: Mapper + Clone,
+{
+    /// Creates a new accessor to the 64-bit register at the physical address `phys_base`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the register is accessed only through the returned accessor,
+    /// and that the register at `phys_base` holds a valid value of `T`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `phys_base` is not 8 byte aligned.
+    pub unsafe fn new(phys_base: usize, mapper: M) -> Self {
+        Self {
+            whole: accessor::Single::new(phys_base, mapper.clone()),
+            dwords: accessor::Array::new(phys_base, 2, mapper),
+            quirks: Quirks::empty(),
+        }
+    }
+
+    /// Makes this accessor work around `quirks` when writing the register.
+    #[must_use]
+    pub fn with_quirks(self, quirks: Quirks) -> Self {
+        Self { quirks, ..self }
+    }
+
+    /// Reads the register.
+    #[must_use]
+    pub fn read(&self) -> T {
+        if cfg!(target_pointer_width = "32") {
+            let lo = u64::from(self.dwords.read_at(0));
+            let hi = u64::from(self.dwords.read_at(1));
+
+            T::from(hi << 32 | lo)
+        } else {
+            self.whole.read()
+        }
+    }
+
+    /// Writes `v` to the register.
+    pub fn write(&mut self, v: T) {
+        let raw: u64 = v.into();
+        let (lo, hi) = (
+            (raw & 0xffff_ffff).try_into().unwrap(),
+            (raw >> 32).try_into().unwrap(),
+        );
+
+        if self.quirks.contains(Quirks::WRITE_64_HI_LO) {
+            self.dwords.write_at(1, hi);
+            self.dwords.write_at(0, lo);
+        } else if cfg!(target_pointer_width = "32") {
+            self.dwords.write_at(0, lo);
+            self.dwords.write_at(1, hi);
+        } else {
+            self.whole.write(v);
+        }
+    }
+
+    /// Reads the register, modifies the value with `f`, and writes it back.
+    pub fn update<U>(&mut self, f: U)
+    where
+        U: FnOnce(&mut T),
+    {
+        let mut v = self.read();
+        f(&mut v);
+        self.write(v);
+    }
+}
+impl<T, M> fmt::Debug for Register64<T, M>
+where
+    T: Copy + Into<u64> + From<u64> + fmt::Debug,
+    M: Mapper + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.read().fmt(f)
+    }
+}
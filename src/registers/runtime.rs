@@ -1,48 +1,628 @@
 //! Host Controller Runtime Registers.
 
-use super::capability::RuntimeRegisterSpaceOffset;
+use super::{
+    capability::{Capability, CapabilityParameters1, RuntimeRegisterSpaceOffset},
+    Register64,
+};
+use crate::{error, quirks::Quirks, Error};
 use accessor::Mapper;
-use core::{convert::TryFrom, fmt};
+use bit_field::BitField;
+use core::{
+    convert::{TryFrom, TryInto},
+    fmt,
+    marker::PhantomData,
+};
 
-/// Interrupt Register Set
-#[repr(C)]
+/// Host Controller Runtime Registers
+///
+/// This struct does not contain the Interrupter Register Sets.
+#[derive(Debug)]
+pub struct Runtime<M>
+where
+    M: Mapper + Clone,
+{
+    /// Microframe Index Register
+    pub mfindex: accessor::Single<MicroframeIndexRegister, M>,
+}
+impl<M> Runtime<M>
+where
+    M: Mapper + Clone,
+{
+    /// Creates a new accessor to the Host Controller Runtime Registers.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the Host Controller Runtime Registers are accessed only through
+    /// this struct.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the base address of the Host Controller Runtime Registers is not
+    /// aligned correctly.
+    pub unsafe fn new(mmio_base: usize, rtoff: RuntimeRegisterSpaceOffset, mapper: &M) -> Self {
+        let base = mmio_base + usize::try_from(rtoff.get()).unwrap();
+
+        Self {
+            mfindex: accessor::Single::new(base, mapper.clone()),
+        }
+    }
+}
+
+/// Microframe Index Register
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct MicroframeIndexRegister(u32);
+impl MicroframeIndexRegister {
+    /// Returns the value of the Microframe Index field.
+    ///
+    /// Use [`MicroframeCounter`] to extend this value to 64 bits.
+    #[must_use]
+    pub fn microframe_index(self) -> u16 {
+        self.0.get_bits(0..=13).try_into().unwrap()
+    }
+}
+impl fmt::Debug for MicroframeIndexRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MicroframeIndexRegister")
+            .field("microframe_index", &self.microframe_index())
+            .finish()
+    }
+}
+
+/// Interrupter Register Set Array
+///
+/// Unlike [`accessor::Array`], this struct does not read or write a whole Interrupter Register Set
+/// at once. Use [`InterruptRegisterSet::interrupter`] to access each register of an interrupter.
+#[derive(Debug)]
+pub struct InterruptRegisterSet<M>
+where
+    M: Mapper + Clone,
+{
+    base: usize,
+    len: usize,
+    mapper: M,
+    quirks: Quirks,
+}
+impl<M> InterruptRegisterSet<M>
+where
+    M: Mapper + Clone,
+{
+    /// Creates an accessor to the Interrupter Register Sets.
+    ///
+    /// The number of the Interrupter Register Sets is the Number of Interrupters field of the
+    /// Structural Parameters 1 Register.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the Host Controller Runtime Registers are accessed only through
+    /// this struct.
+    pub unsafe fn new<M2>(mmio_base: usize, capability: &Capability<M2>, mapper: M) -> Self
+    where
+        M2: Mapper + Clone,
+    {
+        let rtoff = capability.rtsoff.read();
+
+        Self {
+            base: mmio_base + usize::try_from(rtoff.get()).unwrap() + 0x20,
+            len: capability.hcsparams1.read().number_of_interrupts().into(),
+            mapper,
+            quirks: Quirks::empty(),
+        }
+    }
+
+    /// Makes the accessors to the 64-bit registers of the interrupters work around `quirks`.
+    ///
+    /// See [`Register64`] for the affected writes.
+    #[must_use]
+    pub fn with_quirks(self, quirks: Quirks) -> Self {
+        Self { quirks, ..self }
+    }
+
+    /// Returns the number of the Interrupter Register Sets.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there is no Interrupter Register Set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns an accessor to the registers of the `index`th interrupter.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `index` is out of range, or if the base address of the Interrupter
+    /// Register Set is not aligned correctly.
+    pub fn interrupter(&mut self, index: usize) -> Interrupter<'_, M> {
+        assert!(index < self.len, "Index out of range: {}", index);
+
+        // SAFETY: `Self::new` ensures that the registers are accessed only through this struct,
+        // and the returned value borrows `self` mutably.
+        unsafe { Interrupter::new(self.base + index * 0x20, &self.mapper, self.quirks) }
+    }
+
+    /// Reads all the registers of the `index`th interrupter at once.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `index` is out of range, or if the base address of the Interrupter
+    /// Register Set is not aligned correctly.
+    #[must_use]
+    pub fn snapshot(&self, index: usize) -> InterrupterSnapshot {
+        assert!(index < self.len, "Index out of range: {}", index);
+
+        // SAFETY: `Self::new` ensures that the registers are accessed only through this struct,
+        // and the registers are only read.
+        unsafe { Interrupter::new(self.base + index * 0x20, &self.mapper, self.quirks) }.snapshot()
+    }
+
+    /// Returns an iterator which reads the registers of each interrupter.
+    pub fn snapshots(&self) -> impl Iterator<Item = InterrupterSnapshot> + '_ {
+        (0..self.len).map(move |i| self.snapshot(i))
+    }
+
+    // Reads at most `N` Interrupter Register Sets. The rest of the array is `None`.
+    pub(crate) fn read_snapshots<const N: usize>(&self) -> [Option<InterrupterSnapshot>; N] {
+        let mut interrupters = [None; N];
+        for (i, s) in interrupters.iter_mut().zip(self.snapshots()) {
+            *i = Some(s);
+        }
+        interrupters
+    }
+}
+
+/// Interrupter Register Set
+///
+/// Each register is read and written independently.
+#[derive(Debug)]
+pub struct Interrupter<'a, M>
+where
+    M: Mapper + Clone,
+{
+    /// Interrupter Management Register
+    pub iman: accessor::Single<InterrupterManagementRegister, M>,
+    /// Interrupter Moderation Register
+    pub imod: accessor::Single<InterrupterModerationRegister, M>,
+    /// Event Ring Segment Table Size Register
+    pub erstsz: accessor::Single<EventRingSegmentTableSizeRegister, M>,
+    /// Event Ring Segment Table Base Address Register
+    pub erstba: Register64<EventRingSegmentTableBaseAddressRegister, M>,
+    /// Event Ring Dequeue Pointer Register
+    pub erdp: Register64<EventRingDequeuePointerRegister, M>,
+    _marker: PhantomData<&'a mut InterruptRegisterSet<M>>,
+}
+impl<M> Interrupter<'_, M>
+where
+    M: Mapper + Clone,
+{
+    unsafe fn new(base: usize, mapper: &M, quirks: Quirks) -> Self {
+        macro_rules! m {
+            ($offset:expr) => {
+                accessor::Single::new(base + $offset, mapper.clone())
+            };
+        }
+
+        Self {
+            iman: m!(0x00),
+            imod: m!(0x04),
+            erstsz: m!(0x08),
+            erstba: Register64::new(base + 0x10, mapper.clone()).with_quirks(quirks),
+            erdp: Register64::new(base + 0x18, mapper.clone()).with_quirks(quirks),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets the Event Ring Segment Table and the initial Event Ring Dequeue Pointer.
+    ///
+    /// The registers are written in the order of ERSTSZ, ERDP, and ERSTBA, as described in the
+    /// section 4.9.4 of the xHCI specification. Writing ERSTBA makes the xHC start to use the Event
+    /// Ring. The Dequeue ERST Segment Index field is set to 0, so `initial_dequeue` must point to
+    /// the first segment of the Event Ring.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `erst.base` is not 64 byte aligned, or `initial_dequeue` is not 16
+    /// byte aligned. No register is written in this case.
+    pub fn install_event_ring(
+        &mut self,
+        erst: EventRingSegmentTableLocation,
+        initial_dequeue: u64,
+    ) {
+        assert!(
+            erst.base.trailing_zeros() >= 6,
+            "The Event Ring Segment Table must be 64 byte aligned."
+        );
+        assert!(
+            initial_dequeue.trailing_zeros() >= 4,
+            "The Event Ring Dequeue Pointer must be 16 byte aligned."
+        );
+
+        self.erstsz.update(|s| s.set(erst.size));
+        self.erdp.update(|e| {
+            e.set_dequeue_erst_segment_index(0);
+            e.set_event_ring_dequeue_pointer(initial_dequeue);
+        });
+        self.erstba.update(|b| b.set(erst.base));
+    }
+
+    /// Configures the Event Ring, the interrupt moderation, and the interrupt enable of this
+    /// interrupter.
+    ///
+    /// This method calls [`Interrupter::install_event_ring`], writes IMOD, and then calls
+    /// [`Interrupter::set_interrupt_enable`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `c.erst_base` is not 64 byte aligned, or `c.dequeue_pointer` is not
+    /// 16 byte aligned. No register is written in this case.
+    ///
+    /// With the `strict` feature, this method also panics under the same condition as
+    /// [`Interrupter::set_interrupt_enable`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// use xhci::registers::runtime::InterrupterConfiguration;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    ///
+    /// r.interrupt_register_set
+    ///     .interrupter(1)
+    ///     .configure(&InterrupterConfiguration {
+    ///         erst_base: 0x10_0000,
+    ///         erst_size: 1,
+    ///         dequeue_pointer: 0x20_0000,
+    ///         moderation_interval: 4000,
+    ///         interrupt_enable: true,
+    ///     });
+    /// ```
+    pub fn configure(&mut self, c: &InterrupterConfiguration) {
+        self.install_event_ring(
+            EventRingSegmentTableLocation {
+                base: c.erst_base,
+                size: c.erst_size,
+            },
+            c.dequeue_pointer,
+        );
+        self.imod
+            .update(|i| i.set_interrupt_moderation_interval(c.moderation_interval));
+        self.set_interrupt_enable(c.interrupt_enable);
+    }
+
+    /// Configures this interrupter like [`Interrupter::configure`], but returns an error instead
+    /// of panicking or letting the xHC truncate the pointers.
+    ///
+    /// `hccparams1` is the value of the Capability Parameters 1 Register, and is used to check
+    /// whether the xHC can access the pointers. See [`CapabilityParameters1::check_address`].
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::MisalignedPointer`] if `c.erst_base` is not 64 byte aligned or
+    /// `c.dequeue_pointer` is not 16 byte aligned, and [`Error::UnsupportedCapability`] if the xHC
+    /// cannot access them. No register is written in these cases.
+    pub fn try_configure(
+        &mut self,
+        c: &InterrupterConfiguration,
+        hccparams1: CapabilityParameters1,
+    ) -> Result<(), Error> {
+        error::check_alignment(c.erst_base, 64)?;
+        error::check_alignment(c.dequeue_pointer, 16)?;
+        hccparams1.check_address(c.erst_base)?;
+        hccparams1.check_address(c.dequeue_pointer)?;
+
+        self.configure(c);
+        Ok(())
+    }
+
+    /// Sets the Interrupt Enable bit of the Interrupter Management Register.
+    ///
+    /// # Panics
+    ///
+    /// With the `strict` feature, this method panics if `b` is `true` and the Event Handler Busy
+    /// bit is set. This means that the Event Ring Dequeue Pointer Register was not updated after
+    /// handling the events, and the xHC will not assert further interrupts.
+    pub fn set_interrupt_enable(&mut self, b: bool) {
+        strict_assert!(
+            !b || !self.erdp.read().event_handler_busy(),
+            "The Event Ring Dequeue Pointer must be advanced before enabling interrupts."
+        );
+        self.iman.update(|i| i.set_interrupt_enable(b));
+    }
+
+    /// Reads all the registers of this interrupter at once.
+    #[must_use]
+    pub fn snapshot(&self) -> InterrupterSnapshot {
+        InterrupterSnapshot {
+            iman: self.iman.read(),
+            imod: self.imod.read(),
+            erstsz: self.erstsz.read(),
+            erstba: self.erstba.read(),
+            erdp: self.erdp.read(),
+        }
+    }
+}
+
+/// The values of the registers of an Interrupter Register Set.
 #[derive(Copy, Clone, Debug)]
-pub struct InterruptRegisterSet {
-    _iman: u32,
-    _imod: u32,
+pub struct InterrupterSnapshot {
+    /// Interrupter Management Register
+    pub iman: InterrupterManagementRegister,
+    /// Interrupter Moderation Register
+    pub imod: InterrupterModerationRegister,
     /// Event Ring Segment Table Size Register
     pub erstsz: EventRingSegmentTableSizeRegister,
-    _rsvd: u32,
     /// Event Ring Segment Table Base Address Register
     pub erstba: EventRingSegmentTableBaseAddressRegister,
     /// Event Ring Dequeue Pointer Register
     pub erdp: EventRingDequeuePointerRegister,
 }
-impl InterruptRegisterSet {
-    /// Creates an accessor to the Interrupt Register Set.
+
+/// The values of the Host Controller Runtime Registers.
+///
+/// At most `N` Interrupter Register Sets are read when this struct is created. Reading the fields
+/// of this struct does not access the MMIO space.
+#[derive(Copy, Clone)]
+pub struct RuntimeSnapshot<const N: usize> {
+    /// Microframe Index Register
+    pub mfindex: MicroframeIndexRegister,
+    interrupters: [Option<InterrupterSnapshot>; N],
+}
+impl<const N: usize> RuntimeSnapshot<N> {
+    /// Reads the Host Controller Runtime Registers and at most `N` Interrupter Register Sets.
+    #[must_use]
+    pub fn new<M>(runtime: &Runtime<M>, interrupt_register_set: &InterruptRegisterSet<M>) -> Self
+    where
+        M: Mapper + Clone,
+    {
+        Self {
+            mfindex: runtime.mfindex.read(),
+            interrupters: interrupt_register_set.read_snapshots(),
+        }
+    }
+
+    /// Returns an iterator over the read Interrupter Register Sets.
+    pub fn interrupters(&self) -> impl Iterator<Item = &InterrupterSnapshot> {
+        self.interrupters.iter().flatten()
+    }
+}
+impl<const N: usize> fmt::Debug for RuntimeSnapshot<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RuntimeSnapshot")
+            .field("mfindex", &self.mfindex)
+            .field("interrupters", &super::List(|| self.interrupters()))
+            .finish()
+    }
+}
+
+/// The address and the size of an Event Ring Segment Table.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct EventRingSegmentTableLocation {
+    /// The address of the Event Ring Segment Table. It must be 64 byte aligned.
+    pub base: u64,
+    /// The number of the entries of the Event Ring Segment Table.
+    pub size: u16,
+}
+
+/// The values to configure an interrupter with [`Interrupter::configure`].
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct InterrupterConfiguration {
+    /// The address of the Event Ring Segment Table. It must be 64 byte aligned.
+    pub erst_base: u64,
+    /// The number of the entries of the Event Ring Segment Table.
+    pub erst_size: u16,
+    /// The initial Event Ring Dequeue Pointer. It must be 16 byte aligned.
+    pub dequeue_pointer: u64,
+    /// The Interrupt Moderation Interval in 250 ns units.
+    pub moderation_interval: u16,
+    /// Whether to enable the interrupt.
+    pub interrupt_enable: bool,
+}
+
+/// Interrupter Management Register
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct InterrupterManagementRegister(u32);
+impl InterrupterManagementRegister {
+    /// Returns the value of the Interrupt Pending bit.
+    #[must_use]
+    pub fn interrupt_pending(self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    /// Returns the value of the Interrupt Enable bit.
+    #[must_use]
+    pub fn interrupt_enable(self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    /// Sets the value of the Interrupt Enable bit.
     ///
-    /// # Safety
+    /// This method also writes 0 to the Interrupt Pending bit so that writing back the read value
+    /// does not clear it.
+    pub fn set_interrupt_enable(&mut self, b: bool) {
+        self.0.set_bit(0, false);
+        self.0.set_bit(1, b);
+    }
+
+    /// Clears the Interrupt Pending bit, keeping the Interrupt Enable bit.
     ///
-    /// The caller must ensure that the Host Controller Runtime Registers are accessed only through
-    /// this struct.
+    /// The Interrupt Pending bit is cleared by writing 1. Call this method on the read value and
+    /// write it back to acknowledge the interrupt.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    ///
+    /// r.interrupt_register_set
+    ///     .interrupter(0)
+    ///     .iman
+    ///     .update(|i| i.acknowledge());
+    /// ```
+    pub fn acknowledge(&mut self) {
+        self.0.set_bit(0, true);
+    }
+}
+impl fmt::Debug for InterrupterManagementRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InterrupterManagementRegister")
+            .field("interrupt_pending", &self.interrupt_pending())
+            .field("interrupt_enable", &self.interrupt_enable())
+            .finish()
+    }
+}
+
+/// Interrupter Moderation Register
+#[repr(transparent)]
+#[derive(Copy, Clone, Default)]
+pub struct InterrupterModerationRegister(u32);
+impl InterrupterModerationRegister {
+    const INTERVAL_UNIT_NS: u32 = 250;
+
+    /// Returns the value of the Interrupt Moderation Interval field.
+    #[must_use]
+    pub fn interrupt_moderation_interval(self) -> u16 {
+        self.0.get_bits(0..=15).try_into().unwrap()
+    }
+
+    /// Sets the value of the Interrupt Moderation Interval field.
+    ///
+    /// The interval is in 250 ns units. See also
+    /// [`InterrupterModerationRegister::set_interrupt_moderation_interval_ns`] and
+    /// [`InterrupterModerationRegister::set_interrupt_moderation_interval_us`].
+    pub fn set_interrupt_moderation_interval(&mut self, i: u16) {
+        self.0.set_bits(0..=15, i.into());
+    }
+
+    /// Returns the Interrupt Moderation Interval in nanoseconds.
+    #[must_use]
+    pub fn interrupt_moderation_interval_ns(self) -> u32 {
+        u32::from(self.interrupt_moderation_interval()) * Self::INTERVAL_UNIT_NS
+    }
+
+    /// Sets the Interrupt Moderation Interval in nanoseconds.
+    ///
+    /// The interval is rounded down to a multiple of 250 ns.
     ///
     /// # Panics
     ///
-    /// This method panics if the base address of the Interrupt Register Sets is not aligned
-    /// correctly.
-    pub unsafe fn new<M>(
-        mmio_base: usize,
-        rtoff: RuntimeRegisterSpaceOffset,
-        mapper: M,
-    ) -> accessor::Array<Self, M>
-    where
-        M: Mapper,
-    {
-        const NUM_INTERRUPT_REGISTER_SET: usize = 1024;
+    /// This method panics if `ns` is larger than 16,383,750 (about 16 ms).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::registers::runtime::InterrupterModerationRegister;
+    ///
+    /// let mut r = InterrupterModerationRegister::default();
+    /// r.set_interrupt_moderation_interval_ns(1_000);
+    ///
+    /// assert_eq!(r.interrupt_moderation_interval(), 4);
+    /// assert_eq!(r.interrupt_moderation_interval_ns(), 1_000);
+    /// ```
+    pub fn set_interrupt_moderation_interval_ns(&mut self, ns: u32) {
+        let i = u16::try_from(ns / Self::INTERVAL_UNIT_NS)
+            .expect("The Interrupt Moderation Interval is too large.");
+        self.set_interrupt_moderation_interval(i);
+    }
+
+    /// Returns the Interrupt Moderation Interval in microseconds.
+    ///
+    /// The interval is rounded down to a whole microsecond.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::registers::runtime::InterrupterModerationRegister;
+    ///
+    /// let mut r = InterrupterModerationRegister::default();
+    /// r.set_interrupt_moderation_interval_us(125);
+    /// assert_eq!(r.interrupt_moderation_interval_us(), 125);
+    ///
+    /// // 1,750 ns.
+    /// r.set_interrupt_moderation_interval(7);
+    /// assert_eq!(r.interrupt_moderation_interval_us(), 1);
+    /// ```
+    #[must_use]
+    pub fn interrupt_moderation_interval_us(self) -> u16 {
+        (self.interrupt_moderation_interval_ns() / 1000)
+            .try_into()
+            .unwrap()
+    }
+
+    /// Sets the Interrupt Moderation Interval in microseconds.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `us` is larger than 16,383 (about 16 ms).
+    pub fn set_interrupt_moderation_interval_us(&mut self, us: u16) {
+        self.set_interrupt_moderation_interval_ns(u32::from(us) * 1000);
+    }
 
-        let base = mmio_base + usize::try_from(rtoff.get()).unwrap() + 0x20;
+    /// Returns the value of the Interrupt Moderation Counter field.
+    ///
+    /// The counter is in 250 ns units.
+    #[must_use]
+    pub fn interrupt_moderation_counter(self) -> u16 {
+        self.0.get_bits(16..=31).try_into().unwrap()
+    }
 
-        accessor::Array::new(base, NUM_INTERRUPT_REGISTER_SET, mapper)
+    /// Sets the value of the Interrupt Moderation Counter field.
+    ///
+    /// The counter is in 250 ns units.
+    pub fn set_interrupt_moderation_counter(&mut self, c: u16) {
+        self.0.set_bits(16..=31, c.into());
+    }
+}
+impl fmt::Debug for InterrupterModerationRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InterrupterModerationRegister")
+            .field(
+                "interrupt_moderation_interval",
+                &self.interrupt_moderation_interval(),
+            )
+            .field(
+                "interrupt_moderation_counter",
+                &self.interrupt_moderation_counter(),
+            )
+            .finish()
     }
 }
 
@@ -51,6 +631,12 @@ impl InterruptRegisterSet {
 #[derive(Copy, Clone, Debug)]
 pub struct EventRingSegmentTableSizeRegister(u32);
 impl EventRingSegmentTableSizeRegister {
+    /// Returns the number of segments the Event Ring Segment Table supports.
+    #[must_use]
+    pub fn get(self) -> u16 {
+        self.0.get_bits(0..=15).try_into().unwrap()
+    }
+
     /// Sets the number of segments the Event Ring Segment Table supports.
     pub fn set(&mut self, s: u16) {
         self.0 = s.into();
@@ -62,15 +648,44 @@ impl EventRingSegmentTableSizeRegister {
 #[derive(Copy, Clone, Debug)]
 pub struct EventRingSegmentTableBaseAddressRegister(u64);
 impl EventRingSegmentTableBaseAddressRegister {
+    /// Returns the address of the Event Ring Segment Table.
+    #[must_use]
+    pub fn get(self) -> u64 {
+        self.0 & !0b11_1111
+    }
+
     /// Sets the address of the Event Ring Segment Table. It must be 64 byte aligned.
     ///
     /// # Panics
     ///
-    /// This method panics if the address is not 64 byte aligned.
+    /// This method panics if the address is not 64 byte aligned. Use
+    /// [`EventRingSegmentTableBaseAddressRegister::try_set`] to handle the error.
     pub fn set(&mut self, a: u64) {
         assert!(a.trailing_zeros() >= 6);
         self.0 = a;
     }
+
+    /// Sets the address of the Event Ring Segment Table without panicking.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::MisalignedPointer`] if the address is not 64 byte aligned.
+    /// The register is not changed in this case.
+    pub fn try_set(&mut self, a: u64) -> Result<(), Error> {
+        error::check_alignment(a, 64)?;
+        self.set(a);
+        Ok(())
+    }
+}
+impl From<EventRingSegmentTableBaseAddressRegister> for u64 {
+    fn from(b: EventRingSegmentTableBaseAddressRegister) -> Self {
+        b.0
+    }
+}
+impl From<u64> for EventRingSegmentTableBaseAddressRegister {
+    fn from(raw: u64) -> Self {
+        Self(raw)
+    }
 }
 
 /// Event Ring Dequeue Pointer Register.
@@ -78,25 +693,143 @@ impl EventRingSegmentTableBaseAddressRegister {
 #[derive(Copy, Clone)]
 pub struct EventRingDequeuePointerRegister(u64);
 impl EventRingDequeuePointerRegister {
+    /// Returns the value of the Dequeue ERST Segment Index field.
+    #[must_use]
+    pub fn dequeue_erst_segment_index(self) -> u8 {
+        self.0.get_bits(0..=2).try_into().unwrap()
+    }
+
+    /// Sets the value of the Dequeue ERST Segment Index field.
+    ///
+    /// This method also writes 0 to the Event Handler Busy bit so that writing back the read value
+    /// does not clear it.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `i` is larger than 7.
+    pub fn set_dequeue_erst_segment_index(&mut self, i: u8) {
+        assert!(i < 8, "Dequeue ERST Segment Index must be less than 8.");
+        self.0.set_bit(3, false);
+        self.0.set_bits(0..=2, i.into());
+    }
+
+    /// Returns the value of the Event Handler Busy bit.
+    #[must_use]
+    pub fn event_handler_busy(self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    /// Clears the Event Handler Busy bit.
+    ///
+    /// This bit is cleared by writing 1.
+    pub fn clear_event_handler_busy(&mut self) {
+        self.0.set_bit(3, true);
+    }
+
+    // Writes 0 to the Event Handler Busy bit so that writing back the read value does not clear
+    // it.
+    pub(crate) fn keep_event_handler_busy(&mut self) {
+        self.0.set_bit(3, false);
+    }
+
     /// Returns the address of the current Event Ring Dequeue Pointer.
     #[must_use]
     pub fn event_ring_dequeue_pointer(self) -> u64 {
-        self.0 & 0b1111
+        self.0 & !0b1111
     }
 
     /// Sets the address of the current Event Ring Dequeue Pointer. It must be 16 byte aligned.
     ///
+    /// This method keeps the Dequeue ERST Segment Index field, and writes 0 to the Event Handler
+    /// Busy bit so that writing back the read value does not clear it.
+    ///
     /// # Panics
     ///
-    /// This method panics if the address is not 16 byte aligned.
+    /// This method panics if the address is not 16 byte aligned. Use
+    /// [`EventRingDequeuePointerRegister::try_set_event_ring_dequeue_pointer`] to handle the
+    /// error.
     pub fn set_event_ring_dequeue_pointer(&mut self, p: u64) {
-        assert!(p.trailing_zeros() >= 4);
-        self.0 = p;
+        assert!(
+            p.trailing_zeros() >= 4,
+            "The Event Ring Dequeue Pointer must be 16 byte aligned."
+        );
+        self.0 = p | self.0.get_bits(0..=2);
+    }
+
+    /// Sets the address of the current Event Ring Dequeue Pointer without panicking.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::MisalignedPointer`] if the address is not 16 byte aligned.
+    /// The register is not changed in this case.
+    pub fn try_set_event_ring_dequeue_pointer(&mut self, p: u64) -> Result<(), Error> {
+        error::check_alignment(p, 16)?;
+        self.set_event_ring_dequeue_pointer(p);
+        Ok(())
+    }
+
+    /// Sets the address of the current Event Ring Dequeue Pointer and clears the Event Handler
+    /// Busy bit.
+    ///
+    /// Call this method after processing the Event TRBs so that the xHC can assert the next
+    /// interrupt.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the address is not 16 byte aligned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use core::num::NonZeroUsize;
+    /// # use xhci::accessor::Mapper;
+    /// #
+    /// # const MMIO_BASE: usize = 0x1000;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct MemoryMapper;
+    /// # impl Mapper for MemoryMapper {
+    /// #     unsafe fn map(&mut self, phys_base: usize, bytes: usize) -> NonZeroUsize {
+    /// #         unimplemented!()
+    /// #     }
+    /// #
+    /// #     fn unmap(&mut self, virt_base: usize, bytes: usize) {
+    /// #         unimplemented!()
+    /// #     }
+    /// # }
+    /// #
+    /// # let mapper = MemoryMapper;
+    /// # let next_dequeue_pointer = 0x2000;
+    /// let mut r = unsafe { xhci::Registers::new(MMIO_BASE, mapper) };
+    ///
+    /// r.interrupt_register_set
+    ///     .interrupter(0)
+    ///     .erdp
+    ///     .update(|e| e.advance_event_ring_dequeue_pointer(next_dequeue_pointer));
+    /// ```
+    pub fn advance_event_ring_dequeue_pointer(&mut self, p: u64) {
+        self.set_event_ring_dequeue_pointer(p);
+        self.clear_event_handler_busy();
+    }
+}
+impl From<EventRingDequeuePointerRegister> for u64 {
+    fn from(e: EventRingDequeuePointerRegister) -> Self {
+        e.0
+    }
+}
+impl From<u64> for EventRingDequeuePointerRegister {
+    fn from(raw: u64) -> Self {
+        Self(raw)
     }
 }
 impl fmt::Debug for EventRingDequeuePointerRegister {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("EventRingDequeuePointerRegister")
+            .field(
+                "dequeue_erst_segment_index",
+                &self.dequeue_erst_segment_index(),
+            )
+            .field("event_handler_busy", &self.event_handler_busy())
             .field(
                 "event_ring_dequeue_pointer",
                 &self.event_ring_dequeue_pointer(),
@@ -104,3 +837,82 @@ impl fmt::Debug for EventRingDequeuePointerRegister {
             .finish()
     }
 }
+
+/// A monotonically increasing 64-bit microframe counter.
+///
+/// The Microframe Index register has only 14 bits and wraps around every 2.048 seconds. This
+/// struct extends it to 64 bits by counting the wraps, which are detected either from the MFINDEX
+/// Wrap Event TRBs or from the decrease of the values read from the register. A wrap detected by
+/// both of them is counted only once.
+///
+/// # Examples
+///
+/// ```
+/// use xhci::registers::runtime::MicroframeCounter;
+///
+/// let mut c = MicroframeCounter::new();
+///
+/// assert_eq!(c.update(0x3ff0), 0x3ff0);
+/// // The index wrapped around before the MFINDEX Wrap Event TRB was handled.
+/// assert_eq!(c.update(0x0010), 0x4010);
+/// // The MFINDEX Wrap Event TRB for the wrap above.
+/// c.record_wrap();
+/// assert_eq!(c.update(0x0020), 0x4020);
+/// ```
+#[derive(Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct MicroframeCounter {
+    wraps: u64,
+    last: u16,
+    wraps_not_notified: u64,
+    notified_since_update: bool,
+}
+impl MicroframeCounter {
+    const MFINDEX_BITS: u32 = 14;
+
+    /// Creates a new counter which starts from 0.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            wraps: 0,
+            last: 0,
+            wraps_not_notified: 0,
+            notified_since_update: false,
+        }
+    }
+
+    /// Records a wrap notified by a MFINDEX Wrap Event TRB.
+    ///
+    /// Call this method for each MFINDEX Wrap Event TRB, in the order of the events and the reads
+    /// of the Microframe Index register.
+    pub fn record_wrap(&mut self) {
+        if self.wraps_not_notified > 0 {
+            self.wraps_not_notified -= 1;
+        } else {
+            self.wraps += 1;
+            self.notified_since_update = true;
+        }
+    }
+
+    /// Updates the counter with the value read from the Microframe Index register, and returns the
+    /// number of the microframes.
+    ///
+    /// Only the lowest 14 bits of `mfindex` are used.
+    pub fn update(&mut self, mfindex: u16) -> u64 {
+        let mfindex = mfindex & ((1 << Self::MFINDEX_BITS) - 1);
+
+        if mfindex < self.last && !self.notified_since_update {
+            self.wraps += 1;
+            self.wraps_not_notified += 1;
+        }
+
+        self.last = mfindex;
+        self.notified_since_update = false;
+        self.count()
+    }
+
+    /// Returns the number of the microframes at the last update.
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.wraps << Self::MFINDEX_BITS | u64::from(self.last)
+    }
+}
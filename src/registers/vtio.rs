@@ -0,0 +1,65 @@
+//! Virtualization Based Trusted IO Registers.
+
+use super::capability::Capability;
+use accessor::Mapper;
+use core::convert::TryFrom;
+
+/// A dword of the VTIO Register Space.
+///
+/// This crate does not decode the VTIO Registers. Use [`Register::get`] and [`Register::set`] to
+/// access the raw values.
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug)]
+pub struct Register(u32);
+impl Register {
+    /// Creates a new accessor to the VTIO Register Space.
+    ///
+    /// The `i`th element of the returned array is the dword at the offset `i * 4` of the space.
+    ///
+    /// This method returns [`None`] if the xHC does not support the Virtualization Based Trusted
+    /// I/O.
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure that the only one accessor is created, otherwise it may cause undefined
+    /// behavior such as data race.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the base address of the VTIO Register Space is not aligned
+    /// correctly.
+    pub unsafe fn new<M1, M2>(
+        mmio_base: usize,
+        capability: &Capability<M2>,
+        mapper: M1,
+    ) -> Option<accessor::Array<Self, M1>>
+    where
+        M1: Mapper,
+        M2: Mapper + Clone,
+    {
+        // One 4 KiB page, which is the granularity of the VTIO Register Space Offset.
+        const NUM_DWORDS: usize = 1024;
+
+        if !capability
+            .hccparams2
+            .read()
+            .virtualization_based_trusted_io_capability()
+        {
+            return None;
+        }
+
+        let base = mmio_base + usize::try_from(capability.vtiosoff.read().get()).unwrap();
+        Some(accessor::Array::new(base, NUM_DWORDS, mapper))
+    }
+
+    /// Returns the raw value.
+    #[must_use]
+    pub fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Sets the raw value.
+    pub fn set(&mut self, v: u32) {
+        self.0 = v;
+    }
+}
@@ -0,0 +1,376 @@
+//! A parser of raw ring memory dumps for postmortem analysis.
+//!
+//! [`parse`] splits the bytes copied from a ring segment into TRBs and reports the offset, the
+//! Cycle Bit, and the TRB Type of each one. The TRBs can also be decoded with [`Entry::to_event`],
+//! [`Entry::to_command`], and [`Entry::to_transfer`].
+//!
+//! # Examples
+//!
+//! A dump of an Event Ring segment in which the xHC wrote a Command Completion Event and a Port
+//! Status Change Event, and then stopped.
+//!
+//! ```
+//! use xhci::ring::{
+//!     dump,
+//!     trb::{event, Type},
+//! };
+//!
+//! #[rustfmt::skip]
+//! const SEGMENT: [u8; 64] = [
+//!     // Command Completion Event, Success, Slot ID 1.
+//!     0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+//!     0x00, 0x00, 0x00, 0x01, 0x01, 0x84, 0x00, 0x01,
+//!     // Port Status Change Event, Port 3.
+//!     0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00,
+//!     0x00, 0x00, 0x00, 0x01, 0x01, 0x88, 0x00, 0x00,
+//!     // Not written yet.
+//!     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+//!     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+//!     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+//!     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+//! ];
+//!
+//! let entries = dump::parse(&SEGMENT);
+//!
+//! // The Event TRBs written by the xHC have the Cycle Bit set.
+//! let written = entries.filter(|e| e.cycle_bit());
+//! let offsets = written.map(|e| (e.offset(), e.trb_type()));
+//!
+//! assert!(offsets.eq([(0, Ok(Type::CommandCompletion)), (16, Ok(Type::PortStatusChange))]));
+//!
+//! let c = dump::parse(&SEGMENT).next().unwrap().to_event();
+//! if let Ok(event::Allowed::CommandCompletion(c)) = c {
+//!     assert_eq!(c.command_trb_pointer(), 0x2000);
+//!     assert_eq!(c.slot_id(), 1);
+//! } else {
+//!     unreachable!();
+//! }
+//!
+//! let p = dump::parse(&SEGMENT).nth(1).unwrap().to_event();
+//! if let Ok(event::Allowed::PortStatusChange(p)) = p {
+//!     assert_eq!(p.port_id(), 3);
+//! } else {
+//!     unreachable!();
+//! }
+//! ```
+
+use super::trb::{command, event, transfer, ConversionError, Type, BYTES};
+use bit_field::BitField;
+use core::{
+    convert::{TryFrom, TryInto},
+    slice::ChunksExact,
+};
+use num_traits::FromPrimitive;
+
+/// Parses `bytes` as an array of TRBs.
+///
+/// `bytes` must be a copy of a ring segment in the little-endian byte order, which the xHC uses.
+/// The first byte must be the first byte of a TRB. If the length of `bytes` is not a multiple of
+/// [`BYTES`], the last incomplete TRB is ignored.
+///
+/// # Examples
+///
+/// A dump of a Command Ring segment containing an Enable Slot Command, a No Op Command, and a Link
+/// TRB which points to the start of the segment and toggles the cycle state.
+///
+/// ```
+/// use xhci::ring::{dump, trb::Type};
+///
+/// #[rustfmt::skip]
+/// const SEGMENT: [u8; 50] = [
+///     // Enable Slot Command.
+///     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+///     0x00, 0x00, 0x00, 0x00, 0x01, 0x24, 0x00, 0x00,
+///     // No Op Command.
+///     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+///     0x00, 0x00, 0x00, 0x00, 0x01, 0x5c, 0x00, 0x00,
+///     // Link TRB to 0x1000 with the Toggle Cycle bit set.
+///     0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+///     0x00, 0x00, 0x00, 0x00, 0x03, 0x18, 0x00, 0x00,
+///     // An incomplete TRB, which is ignored.
+///     0xff, 0xff,
+/// ];
+///
+/// let entries = dump::parse(&SEGMENT);
+/// assert_eq!(entries.len(), 3);
+///
+/// let types = entries.map(|e| e.trb_type());
+/// assert!(types.eq([Ok(Type::EnableSlot), Ok(Type::NoopCommand), Ok(Type::Link)]));
+///
+/// let link = dump::parse(&SEGMENT).last().unwrap();
+/// assert_eq!(link.offset(), 32);
+/// assert_eq!(link.raw(), [0x1000, 0, 0, 0x1803]);
+/// ```
+#[must_use]
+pub fn parse(bytes: &[u8]) -> Entries<'_> {
+    Entries {
+        chunks: bytes.chunks_exact(BYTES),
+        offset: 0,
+    }
+}
+
+/// An iterator over the TRBs in a ring memory dump.
+///
+/// This struct is created by [`parse`].
+#[derive(Clone, Debug)]
+pub struct Entries<'a> {
+    chunks: ChunksExact<'a, u8>,
+    offset: usize,
+}
+impl Iterator for Entries<'_> {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let c = self.chunks.next()?;
+
+        let mut raw = [0; 4];
+        for (d, b) in raw.iter_mut().zip(c.chunks_exact(4)) {
+            *d = u32::from_le_bytes(b.try_into().unwrap());
+        }
+
+        let e = Entry {
+            offset: self.offset,
+            raw,
+        };
+        self.offset += BYTES;
+
+        Some(e)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+impl ExactSizeIterator for Entries<'_> {}
+
+/// A TRB in a ring memory dump.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Entry {
+    offset: usize,
+    raw: [u32; 4],
+}
+impl Entry {
+    /// Returns the offset of this TRB from the start of the dump in bytes.
+    #[must_use]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the raw dwords of this TRB.
+    #[must_use]
+    pub fn raw(&self) -> [u32; 4] {
+        self.raw
+    }
+
+    /// Returns the value of the Cycle Bit.
+    #[must_use]
+    pub fn cycle_bit(&self) -> bool {
+        self.raw[3].get_bit(0)
+    }
+
+    /// Returns the value of the TRB Type field.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the raw value of the field if it is not a known TRB Type. A TRB which
+    /// has never been written has the value 0.
+    pub fn trb_type(&self) -> Result<Type, u8> {
+        let t: u8 = self.raw[3].get_bits(10..=15).try_into().unwrap();
+        Type::from_u8(t).ok_or(t)
+    }
+
+    /// Decodes this TRB as an Event TRB.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if this TRB is not a valid Event TRB.
+    ///
+    /// # Examples
+    ///
+    /// A dump of an Event Ring segment of 8 TRBs. The xHC filled the segment with the Cycle Bit
+    /// set, wrapped around, and wrote two more TRBs with the Cycle Bit cleared.
+    ///
+    /// ```
+    /// use xhci::ring::{
+    ///     dump,
+    ///     trb::event::{Allowed, CompletionCode},
+    /// };
+    ///
+    /// const SEGMENT: &[u8] = include_bytes!("../../tests/ring_dumps/event_ring.bin");
+    ///
+    /// let cycles = dump::parse(SEGMENT).map(|e| e.cycle_bit());
+    /// assert!(cycles.eq([false, false, true, true, true, true, true, true]));
+    ///
+    /// let event = |i| dump::parse(SEGMENT).nth(i).unwrap().to_event().unwrap();
+    ///
+    /// // The newest TRB: a Short Packet of the Normal TRB at the start of the Transfer Ring.
+    /// if let Allowed::TransferEvent(e) = event(0) {
+    ///     assert_eq!(e.trb_pointer(), 0x30_0000);
+    ///     assert_eq!(e.completion_code(), Ok(CompletionCode::ShortPacket));
+    ///     assert_eq!(e.trb_transfer_length(), 0x100);
+    ///     assert_eq!((e.slot_id(), e.endpoint_id()), (1, 3));
+    /// } else {
+    ///     unreachable!();
+    /// }
+    ///
+    /// if let Allowed::CommandCompletion(c) = event(1) {
+    ///     assert_eq!(c.command_trb_pointer(), 0x10_0000);
+    ///     assert_eq!(c.completion_code(), Ok(CompletionCode::Success));
+    /// } else {
+    ///     unreachable!();
+    /// }
+    ///
+    /// // The oldest TRB which has not been overwritten.
+    /// if let Allowed::TransferEvent(e) = event(2) {
+    ///     assert_eq!(e.trb_pointer(), 0x30_0030);
+    ///     assert_eq!(e.completion_code(), Ok(CompletionCode::Success));
+    /// } else {
+    ///     unreachable!();
+    /// }
+    ///
+    /// if let Allowed::TransferEvent(e) = event(3) {
+    ///     assert_eq!(e.completion_code(), Ok(CompletionCode::StallError));
+    /// } else {
+    ///     unreachable!();
+    /// }
+    ///
+    /// if let Allowed::PortStatusChange(p) = event(7) {
+    ///     assert_eq!(p.port_id(), 2);
+    /// } else {
+    ///     unreachable!();
+    /// }
+    /// ```
+    pub fn to_event(&self) -> Result<event::Allowed, ConversionError> {
+        event::Allowed::try_from(self.raw)
+    }
+
+    /// Decodes this TRB as a Command TRB.
+    ///
+    /// Only the TRB Type is checked. The reserved bits are not.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if this TRB is not a Command TRB.
+    ///
+    /// # Examples
+    ///
+    /// A dump of a Command Ring segment of 8 TRBs at `0x10_0000`. The software filled the segment
+    /// with the Cycle Bit set, followed the Link TRB which toggles the cycle state, and wrote two
+    /// more TRBs with the Cycle Bit cleared.
+    ///
+    /// ```
+    /// use xhci::ring::{dump, trb::command::Allowed};
+    ///
+    /// const SEGMENT: &[u8] = include_bytes!("../../tests/ring_dumps/command_ring.bin");
+    ///
+    /// let cycles = dump::parse(SEGMENT).map(|e| e.cycle_bit());
+    /// assert!(cycles.eq([false, false, true, true, true, true, true, true]));
+    ///
+    /// let command = |i| dump::parse(SEGMENT).nth(i).unwrap().to_command().unwrap();
+    ///
+    /// if let Allowed::EvaluateContext(c) = command(0) {
+    ///     assert_eq!(c.input_context_pointer(), 0x20_0000);
+    ///     assert_eq!(c.slot_id(), 1);
+    /// } else {
+    ///     unreachable!();
+    /// }
+    ///
+    /// assert!(matches!(command(1), Allowed::Noop(_)));
+    /// assert!(matches!(command(2), Allowed::AddressDevice(_)));
+    /// assert!(matches!(command(3), Allowed::ConfigureEndpoint(_)));
+    /// assert!(matches!(command(4), Allowed::ResetEndpoint(_)));
+    ///
+    /// if let Allowed::SetTrDequeuePointer(c) = command(5) {
+    ///     assert_eq!(c.new_tr_dequeue_pointer(), 0x30_0040);
+    ///     assert!(c.dequeue_cycle_state());
+    ///     assert_eq!((c.slot_id(), c.endpoint_id()), (1, 3));
+    /// } else {
+    ///     unreachable!();
+    /// }
+    ///
+    /// if let Allowed::Link(l) = command(7) {
+    ///     assert_eq!(l.ring_segment_pointer(), 0x10_0000);
+    ///     assert!(l.toggle_cycle());
+    /// } else {
+    ///     unreachable!();
+    /// }
+    ///
+    /// // A TRB which has never been written is not a Command TRB.
+    /// assert!(dump::parse(&[0; 16]).next().unwrap().to_command().is_err());
+    /// ```
+    pub fn to_command(&self) -> Result<command::Allowed, ConversionError> {
+        command::Allowed::try_from(self.raw)
+    }
+
+    /// Decodes this TRB as a Transfer TRB.
+    ///
+    /// Only the TRB Type is checked. The reserved bits are not.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if this TRB is not a Transfer TRB.
+    ///
+    /// # Examples
+    ///
+    /// A dump of a Transfer Ring segment of 8 TRBs at `0x30_0000`. It contains a `GET_DESCRIPTOR`
+    /// control transfer, a bulk transfer split into two chained Normal TRBs, and a Link TRB. The
+    /// software has wrapped around and overwritten the first TRB with the Cycle Bit cleared.
+    ///
+    /// ```
+    /// use xhci::ring::{
+    ///     dump,
+    ///     trb::transfer::{Allowed, Direction, TransferType},
+    /// };
+    ///
+    /// const SEGMENT: &[u8] = include_bytes!("../../tests/ring_dumps/transfer_ring.bin");
+    ///
+    /// let cycles = dump::parse(SEGMENT).map(|e| e.cycle_bit());
+    /// assert!(cycles.eq([false, true, true, true, true, true, true, true]));
+    ///
+    /// let trb = |i| dump::parse(SEGMENT).nth(i).unwrap().to_transfer().unwrap();
+    ///
+    /// if let Allowed::Normal(n) = trb(0) {
+    ///     assert_eq!(n.data_buffer_pointer(), 0x43_0000);
+    ///     assert_eq!(n.trb_transfer_length(), 0x200);
+    /// } else {
+    ///     unreachable!();
+    /// }
+    ///
+    /// if let Allowed::SetupStage(s) = trb(1) {
+    ///     assert_eq!((s.request_type(), s.request(), s.value()), (0x80, 6, 0x0100));
+    ///     assert_eq!(s.length(), 18);
+    ///     assert_eq!(s.transfer_type(), TransferType::In);
+    /// } else {
+    ///     unreachable!();
+    /// }
+    ///
+    /// if let Allowed::DataStage(d) = trb(2) {
+    ///     assert_eq!(d.data_buffer_pointer(), 0x40_0000);
+    ///     assert_eq!(d.direction(), Direction::In);
+    /// } else {
+    ///     unreachable!();
+    /// }
+    ///
+    /// assert!(matches!(trb(3), Allowed::StatusStage(_)));
+    ///
+    /// if let (Allowed::Normal(first), Allowed::Normal(last)) = (trb(4), trb(5)) {
+    ///     assert!(first.chain_bit());
+    ///     assert!(!last.chain_bit());
+    ///     assert!(last.interrupt_on_completion());
+    /// } else {
+    ///     unreachable!();
+    /// }
+    ///
+    /// assert!(matches!(trb(6), Allowed::Noop(_)));
+    ///
+    /// if let Allowed::Link(l) = trb(7) {
+    ///     assert_eq!(l.ring_segment_pointer(), 0x30_0000);
+    ///     assert!(l.toggle_cycle());
+    /// } else {
+    ///     unreachable!();
+    /// }
+    /// ```
+    pub fn to_transfer(&self) -> Result<transfer::Allowed, ConversionError> {
+        transfer::Allowed::try_from(self.raw)
+    }
+}
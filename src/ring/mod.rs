@@ -1,3 +1,4 @@
 //! TRB Ring.
 
+pub mod dump;
 pub mod trb;
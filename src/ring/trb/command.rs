@@ -1,8 +1,9 @@
 //! Command TRBs.
 
 use super::Link;
+use crate::ids::{EndpointId, SlotId};
 use bit_field::BitField;
-use core::convert::TryInto;
+use core::convert::{TryFrom, TryInto};
 
 allowed! {
     /// TRBs which are allowed to be pushed to the Command Ring.
@@ -46,6 +47,26 @@ allowed! {
     }
 }
 
+// The Command TRBs are written by the software, so only the TRB Type is checked when converting
+// the raw dwords.
+reserved!(EnableSlot(Type::EnableSlot) {});
+reserved!(DisableSlot(Type::DisableSlot) {});
+reserved!(AddressDevice(Type::AddressDevice) {});
+reserved!(ConfigureEndpoint(Type::ConfigureEndpoint) {});
+reserved!(EvaluateContext(Type::EvaluateContext) {});
+reserved!(ResetEndpoint(Type::ResetEndpoint) {});
+reserved!(StopEndpoint(Type::StopEndpoint) {});
+reserved!(SetTrDequeuePointer(Type::SetTrDequeuePointer) {});
+reserved!(ResetDevice(Type::ResetDevice) {});
+reserved!(ForceEvent(Type::ForceEvent) {});
+reserved!(NegotiateBandwidth(Type::NegotiateBandwidth) {});
+reserved!(SetLatencyToleranceValue(Type::SetLatencyToleranceValue) {});
+reserved!(GetPortBandwidth(Type::GetPortBandwidth) {});
+reserved!(ForceHeader(Type::ForceHeader) {});
+reserved!(Noop(Type::NoopCommand) {});
+reserved!(GetExtendedProperty(Type::GetExtendedProperty) {});
+reserved!(SetExtendedProperty(Type::SetExtendedProperty) {});
+
 add_trb_with_default!(Noop, "No Op Command TRB", Type::NoopCommand);
 impl_debug_for_trb!(Noop {});
 
@@ -863,3 +884,258 @@ impl_debug_for_trb!(SetExtendedProperty {
     endpoint_id,
     slot_id
 });
+
+typed_slot_id_setter!(
+    DisableSlot,
+    AddressDevice,
+    ConfigureEndpoint,
+    EvaluateContext,
+    ResetEndpoint,
+    StopEndpoint,
+    SetTrDequeuePointer,
+    ResetDevice,
+    NegotiateBandwidth,
+    GetExtendedProperty,
+    SetExtendedProperty
+);
+typed_endpoint_id_setter!(
+    ResetEndpoint,
+    StopEndpoint,
+    SetTrDequeuePointer,
+    GetExtendedProperty,
+    SetExtendedProperty
+);
+
+/// A builder of the Command TRBs which validates the combination of the fields.
+///
+/// Unlike the setters of the Command TRBs, the methods of this builder never panic. The invalid
+/// values are reported as a [`BuildError`] by [`Builder::build`].
+///
+/// # Examples
+///
+/// ```
+/// use xhci::ids::SlotId;
+/// use xhci::ring::trb::command::{AddressDevice, BuildError, Builder};
+///
+/// # let input_context_addr = 0x1000;
+/// let c = Builder::new(AddressDevice::new())
+///     .slot_id(SlotId::new(1).unwrap())
+///     .pointer(input_context_addr)
+///     .build();
+/// assert!(c.is_ok());
+///
+/// let c = Builder::new(AddressDevice::new()).pointer(0x1000).build();
+/// assert_eq!(c, Err(BuildError::ZeroSlotId));
+/// ```
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Builder {
+    trb: Allowed,
+    slot_id: Option<SlotId>,
+    endpoint_id: Option<EndpointId>,
+    pointer: Option<u64>,
+}
+impl Builder {
+    /// Creates a new builder from the Command TRB.
+    ///
+    /// The fields which are already set in `trb` are also validated by [`Builder::build`].
+    pub fn new<T: Into<Allowed>>(trb: T) -> Self {
+        Self {
+            trb: trb.into(),
+            slot_id: None,
+            endpoint_id: None,
+            pointer: None,
+        }
+    }
+
+    /// Sets the value of the Slot ID field.
+    pub fn slot_id(&mut self, i: SlotId) -> &mut Self {
+        self.slot_id = Some(i);
+        self
+    }
+
+    /// Sets the value of the Endpoint ID field.
+    pub fn endpoint_id(&mut self, i: EndpointId) -> &mut Self {
+        self.endpoint_id = Some(i);
+        self
+    }
+
+    /// Sets the value of the pointer field of the Command TRB.
+    ///
+    /// This is the Ring Segment Pointer of the Link TRB, the Input Context Pointer of the Address
+    /// Device, Configure Endpoint and Evaluate Context Command TRB, the New TR Dequeue Pointer of
+    /// the Set TR Dequeue Pointer Command TRB, the Event TRB Pointer of the Force Event Command TRB,
+    /// the Port Bandwidth Context Pointer of the Get Port Bandwidth Command TRB, and the Extended
+    /// Property Context Pointer of the Get Extended Property Command TRB.
+    pub fn pointer(&mut self, p: u64) -> &mut Self {
+        self.pointer = Some(p);
+        self
+    }
+
+    /// Validates the fields and returns the Command TRB.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an [`Err`] value if the Command TRB does not have a field set to this
+    /// builder, or if a field has an invalid value.
+    pub fn build(&self) -> Result<Allowed, BuildError> {
+        let mut t = self.trb;
+
+        if let Some(p) = self.pointer {
+            set_pointer(&mut t, p)?;
+        }
+        if let Some(i) = self.slot_id {
+            set_slot_id(&mut t, i)?;
+        }
+        if let Some(i) = self.endpoint_id {
+            set_endpoint_id(&mut t, i)?;
+        }
+
+        validate(&t)?;
+        Ok(t)
+    }
+}
+
+/// An error returned by [`Builder::build`].
+///
+/// This error can be converted into [`crate::Error`].
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum BuildError {
+    /// The Command TRB does not have the field.
+    NoSuchField(&'static str),
+    /// The Slot ID field of the Command TRB passed to [`Builder::new`] is 0 though the command
+    /// targets a Device Slot.
+    ZeroSlotId,
+    /// The Endpoint ID field of the Command TRB passed to [`Builder::new`] is not in the range
+    /// `1..=31`.
+    InvalidEndpointId(u8),
+    /// The pointer is 0 though the command requires a valid address.
+    NullPointer,
+    /// The pointer is not aligned to `alignment` bytes.
+    MisalignedPointer {
+        /// The given pointer.
+        pointer: u64,
+        /// The required alignment in bytes.
+        alignment: u64,
+    },
+}
+
+macro_rules! slot_id_commands {
+    ($t:expr, $c:ident => $e:expr, _ => $default:expr) => {
+        match $t {
+            Allowed::DisableSlot($c) => $e,
+            Allowed::AddressDevice($c) => $e,
+            Allowed::ConfigureEndpoint($c) => $e,
+            Allowed::EvaluateContext($c) => $e,
+            Allowed::ResetEndpoint($c) => $e,
+            Allowed::StopEndpoint($c) => $e,
+            Allowed::SetTrDequeuePointer($c) => $e,
+            Allowed::ResetDevice($c) => $e,
+            Allowed::NegotiateBandwidth($c) => $e,
+            _ => $default,
+        }
+    };
+}
+macro_rules! endpoint_id_commands {
+    ($t:expr, $c:ident => $e:expr, _ => $default:expr) => {
+        match $t {
+            Allowed::ResetEndpoint($c) => $e,
+            Allowed::StopEndpoint($c) => $e,
+            Allowed::SetTrDequeuePointer($c) => $e,
+            _ => $default,
+        }
+    };
+}
+
+// The setters return different types, so each of the eight arms needs a block.
+#[allow(clippy::too_many_lines)]
+fn set_pointer(t: &mut Allowed, p: u64) -> Result<(), BuildError> {
+    if p.trailing_zeros() < 4 {
+        return Err(BuildError::MisalignedPointer {
+            pointer: p,
+            alignment: 16,
+        });
+    }
+
+    match t {
+        Allowed::Link(c) => {
+            c.set_ring_segment_pointer(p);
+        }
+        Allowed::AddressDevice(c) => {
+            c.set_input_context_pointer(p);
+        }
+        Allowed::ConfigureEndpoint(c) => {
+            c.set_input_context_pointer(p);
+        }
+        Allowed::EvaluateContext(c) => {
+            c.set_input_context_pointer(p);
+        }
+        Allowed::SetTrDequeuePointer(c) => {
+            c.set_new_tr_dequeue_pointer(p);
+        }
+        Allowed::ForceEvent(c) => {
+            c.set_event_trb_pointer(p);
+        }
+        Allowed::GetPortBandwidth(c) => {
+            c.set_port_bandwidth_context_pointer(p);
+        }
+        Allowed::GetExtendedProperty(c) => {
+            c.set_extended_property_context_pointer(p);
+        }
+        _ => return Err(BuildError::NoSuchField("pointer")),
+    }
+    Ok(())
+}
+
+fn set_slot_id(t: &mut Allowed, i: SlotId) -> Result<(), BuildError> {
+    match t {
+        Allowed::GetExtendedProperty(c) => {
+            c.set_slot(i);
+        }
+        Allowed::SetExtendedProperty(c) => {
+            c.set_slot(i);
+        }
+        _ => slot_id_commands!(t, c => { c.set_slot(i); },
+            _ => return Err(BuildError::NoSuchField("Slot ID"))),
+    }
+    Ok(())
+}
+
+fn set_endpoint_id(t: &mut Allowed, i: EndpointId) -> Result<(), BuildError> {
+    match t {
+        Allowed::GetExtendedProperty(c) => {
+            c.set_endpoint(i);
+        }
+        Allowed::SetExtendedProperty(c) => {
+            c.set_endpoint(i);
+        }
+        _ => endpoint_id_commands!(t, c => { c.set_endpoint(i); },
+            _ => return Err(BuildError::NoSuchField("Endpoint ID"))),
+    }
+    Ok(())
+}
+
+fn validate(t: &Allowed) -> Result<(), BuildError> {
+    if slot_id_commands!(t, c => c.slot().is_err(), _ => false) {
+        Err(BuildError::ZeroSlotId)
+    } else if let Some(Err(i)) = endpoint_id_commands!(t, c => Some(c.endpoint()), _ => None) {
+        Err(BuildError::InvalidEndpointId(i))
+    } else if pointer(t) == Some(0) {
+        Err(BuildError::NullPointer)
+    } else {
+        Ok(())
+    }
+}
+
+fn pointer(t: &Allowed) -> Option<u64> {
+    match t {
+        Allowed::Link(c) => Some(c.ring_segment_pointer()),
+        Allowed::AddressDevice(c) => Some(c.input_context_pointer()),
+        Allowed::ConfigureEndpoint(c) if !c.deconfigure() => Some(c.input_context_pointer()),
+        Allowed::EvaluateContext(c) => Some(c.input_context_pointer()),
+        Allowed::SetTrDequeuePointer(c) => Some(c.new_tr_dequeue_pointer()),
+        Allowed::ForceEvent(c) => Some(c.event_trb_pointer()),
+        Allowed::GetPortBandwidth(c) => Some(c.port_bandwidth_context_pointer()),
+        Allowed::GetExtendedProperty(c) => Some(c.extended_property_context_pointer()),
+        _ => None,
+    }
+}
@@ -1,5 +1,7 @@
 //! Event TRBs.
 
+use super::{ConversionError, Type};
+use crate::{ids::SlotId, quirks::Quirks};
 use bit_field::BitField;
 use core::convert::{TryFrom, TryInto};
 use num_derive::FromPrimitive;
@@ -26,29 +28,155 @@ allowed! {
         MfindexWrap
     }
 }
-impl TryFrom<[u32; 4]> for Allowed {
-    type Error = [u32; 4];
-
-    fn try_from(raw: [u32; 4]) -> Result<Self, Self::Error> {
-        macro_rules! try_from {
-            ($name:ident) => {
-                if let Ok(t) = $name::try_from(raw) {
-                    return Ok(Self::$name(t));
+impl Allowed {
+    /// Converts the raw dwords into an Event TRB, taking `quirks` into account.
+    ///
+    /// If `quirks` contains [`Quirks::VIRTUAL_CONTROLLER`], the reserved bits are cleared instead
+    /// of being rejected. Otherwise, this method is the same as [`Allowed::try_from`].
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the TRB Type is not one of the Event TRBs, or if some of the
+    /// reserved bits are set and `quirks` does not contain [`Quirks::VIRTUAL_CONTROLLER`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::{quirks::Quirks, ring::trb::event::Allowed};
+    ///
+    /// // A Port Status Change Event TRB with the reserved bits of the dword 2 set.
+    /// let raw = [1 << 24, 0, 0x0100_00ff, 34 << 10 | 1];
+    ///
+    /// assert!(Allowed::try_from_with_quirks(raw, Quirks::empty()).is_err());
+    /// assert!(Allowed::try_from_with_quirks(raw, Quirks::VIRTUAL_CONTROLLER).is_ok());
+    /// ```
+    pub fn try_from_with_quirks(
+        mut raw: [u32; 4],
+        quirks: Quirks,
+    ) -> Result<Self, ConversionError> {
+        loop {
+            match Self::try_from(raw) {
+                Err(ConversionError::ReservedBitsSet {
+                    dword,
+                    first_bit,
+                    last_bit,
+                    ..
+                }) if quirks.contains(Quirks::VIRTUAL_CONTROLLER) => {
+                    raw[dword].set_bits(first_bit..=last_bit, 0);
                 }
-            };
+                r => return r,
+            }
         }
+    }
 
-        try_from!(TransferEvent);
-        try_from!(CommandCompletion);
-        try_from!(PortStatusChange);
-        try_from!(BandwidthRequest);
-        try_from!(Doorbell);
-        try_from!(HostController);
-        try_from!(DeviceNotification);
-        try_from!(MfindexWrap);
+    /// Returns the value of the Slot ID field.
+    ///
+    /// This method returns [`None`] if the Event TRB does not have the Slot ID field.
+    #[must_use]
+    pub fn slot_id(&self) -> Option<u8> {
+        match self {
+            Self::TransferEvent(e) => Some(e.slot_id()),
+            Self::CommandCompletion(e) => Some(e.slot_id()),
+            Self::BandwidthRequest(e) => Some(e.slot_id()),
+            Self::Doorbell(e) => Some(e.slot_id()),
+            Self::DeviceNotification(e) => Some(e.slot_id()),
+            Self::PortStatusChange(_) | Self::HostController(_) | Self::MfindexWrap(_) => None,
+        }
+    }
 
-        Err(raw)
+    /// Returns the value of the Slot ID field as a [`SlotId`].
+    ///
+    /// This method returns [`None`] if the Event TRB does not have the Slot ID field, or if the
+    /// field is 0.
+    #[must_use]
+    pub fn slot(&self) -> Option<SlotId> {
+        self.slot_id().and_then(SlotId::new)
     }
+
+    /// Returns the Completion Code.
+    ///
+    /// # Errors
+    ///
+    /// This method may return an [`Err`] value with the Completion Code that is either reserved or
+    /// not implemented by this crate.
+    pub fn completion_code(&self) -> Result<CompletionCode, u8> {
+        match self {
+            Self::TransferEvent(e) => e.completion_code(),
+            Self::CommandCompletion(e) => e.completion_code(),
+            Self::PortStatusChange(e) => e.completion_code(),
+            Self::BandwidthRequest(e) => e.completion_code(),
+            Self::Doorbell(e) => e.completion_code(),
+            Self::HostController(e) => e.completion_code(),
+            Self::DeviceNotification(e) => e.completion_code(),
+            Self::MfindexWrap(e) => e.completion_code(),
+        }
+    }
+
+    /// Passes the Event TRB to the method of `h` which corresponds to the type of the TRB.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::ring::trb::event::{Allowed, EventHandler, PortStatusChange};
+    ///
+    /// struct Handler {
+    ///     port_changes: usize,
+    /// }
+    /// impl EventHandler for Handler {
+    ///     fn on_port_status_change(&mut self, _: PortStatusChange) {
+    ///         self.port_changes += 1;
+    ///     }
+    /// }
+    ///
+    /// let mut h = Handler { port_changes: 0 };
+    /// Allowed::PortStatusChange(PortStatusChange::new()).dispatch(&mut h);
+    ///
+    /// assert_eq!(h.port_changes, 1);
+    /// ```
+    pub fn dispatch<H: EventHandler + ?Sized>(self, h: &mut H) {
+        match self {
+            Self::TransferEvent(e) => h.on_transfer_event(e),
+            Self::CommandCompletion(e) => h.on_command_completion(e),
+            Self::PortStatusChange(e) => h.on_port_status_change(e),
+            Self::BandwidthRequest(e) => h.on_bandwidth_request(e),
+            Self::Doorbell(e) => h.on_doorbell(e),
+            Self::HostController(e) => h.on_host_controller(e),
+            Self::DeviceNotification(e) => h.on_device_notification(e),
+            Self::MfindexWrap(e) => h.on_mfindex_wrap(e),
+        }
+    }
+}
+
+/// A trait to handle the Event TRBs.
+///
+/// Each method is called by [`Allowed::dispatch`] with the Event TRB of the corresponding type.
+/// All the methods do nothing by default, so implement only the methods for the events you are
+/// interested in.
+#[allow(clippy::module_name_repetitions)]
+pub trait EventHandler {
+    /// Handles a Transfer Event TRB.
+    fn on_transfer_event(&mut self, _: TransferEvent) {}
+
+    /// Handles a Command Completion Event TRB.
+    fn on_command_completion(&mut self, _: CommandCompletion) {}
+
+    /// Handles a Port Status Change Event TRB.
+    fn on_port_status_change(&mut self, _: PortStatusChange) {}
+
+    /// Handles a Bandwidth Request Event TRB.
+    fn on_bandwidth_request(&mut self, _: BandwidthRequest) {}
+
+    /// Handles a Doorbell Event TRB.
+    fn on_doorbell(&mut self, _: Doorbell) {}
+
+    /// Handles a Host Controller Event TRB.
+    fn on_host_controller(&mut self, _: HostController) {}
+
+    /// Handles a Device Notification Event TRB.
+    fn on_device_notification(&mut self, _: DeviceNotification) {}
+
+    /// Handles a MFINDEX Wrap Event TRB.
+    fn on_mfindex_wrap(&mut self, _: MfindexWrap) {}
 }
 
 macro_rules! completion_code {
@@ -138,6 +266,48 @@ impl TransferEvent {
         self.0[3].get_bit(2)
     }
 
+    /// Returns the number of bytes actually transferred.
+    ///
+    /// `requested` must be the sum of the TRB Transfer Length of the TRBs of the TD, from the first
+    /// one up to and including the TRB pointed by the TRB Pointer field. If the TD consists of a
+    /// single TRB, or if the event is generated for the last TRB of the TD, this is the length of
+    /// the whole TD.
+    ///
+    /// If the Event Data bit is set, or if the Completion Code is Stopped - Short Packet, the TRB
+    /// Transfer Length field contains the number of the transferred bytes, and this method returns
+    /// it as is. Otherwise the field contains the residual number of bytes which were not
+    /// transferred, and this method subtracts it from `requested`.
+    ///
+    /// This method returns [`None`] if the length is unknown, which is when the Completion Code is
+    /// Invalid, Stopped - Length Invalid, reserved, or not implemented by this crate, or when the
+    /// residual is larger than `requested`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xhci::ring::trb::event::TransferEvent;
+    ///
+    /// # let requested_length = 512;
+    /// let e = TransferEvent::new();
+    ///
+    /// // The Completion Code of `e` is Invalid.
+    /// assert_eq!(e.actual_transfer_length(requested_length), None);
+    /// ```
+    #[must_use]
+    pub fn actual_transfer_length(&self, requested: u32) -> Option<u32> {
+        let l = self.trb_transfer_length();
+
+        if self.event_data() {
+            return Some(l);
+        }
+
+        match self.completion_code() {
+            Ok(CompletionCode::StoppedShortPacket) => Some(l),
+            Ok(CompletionCode::Invalid | CompletionCode::StoppedLengthInvalid) | Err(_) => None,
+            Ok(_) => requested.checked_sub(l),
+        }
+    }
+
     /// Returns the value of the Endpoint ID field.
     #[must_use]
     pub fn endpoint_id(&self) -> u8 {
@@ -183,6 +353,38 @@ impl CommandCompletion {
         self.0[2].get_bits(0..=23)
     }
 
+    /// Interprets the Command Completion Parameter field as the result of the command of type
+    /// `t`.
+    ///
+    /// `t` is the type of the Command TRB pointed by the Command TRB Pointer field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::convert::TryFrom;
+    /// use xhci::ring::trb::{
+    ///     event::{CommandCompletion, DecodedParameter},
+    ///     Type,
+    /// };
+    ///
+    /// let c = CommandCompletion::try_from([0, 0, 0x42, 33 << 10]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     c.parameter_for(Type::GetExtendedProperty),
+    ///     DecodedParameter::ExtendedProperty(0x42)
+    /// );
+    /// assert_eq!(c.parameter_for(Type::NoopCommand), DecodedParameter::NotDefined);
+    /// ```
+    #[must_use]
+    pub fn parameter_for(&self, t: Type) -> DecodedParameter {
+        match t {
+            Type::GetExtendedProperty => {
+                DecodedParameter::ExtendedProperty(self.command_completion_parameter())
+            }
+            _ => DecodedParameter::NotDefined,
+        }
+    }
+
     /// Returns the value of the VF (Virtual Function) ID field.
     #[must_use]
     pub fn vf_id(&self) -> u8 {
@@ -236,8 +438,24 @@ impl Doorbell {
     pub fn db_reason(&self) -> u8 {
         self.0[0].get_bits(0..=4).try_into().unwrap()
     }
+
+    /// Returns the value of the VF ID field.
+    #[must_use]
+    pub fn vf_id(&self) -> u8 {
+        self.0[3].get_bits(16..=23).try_into().unwrap()
+    }
+
+    /// Returns the value of the Slot ID field.
+    #[must_use]
+    pub fn slot_id(&self) -> u8 {
+        self.0[3].get_bits(24..=31).try_into().unwrap()
+    }
 }
-impl_debug_for_event_trb!(Doorbell { db_reason });
+impl_debug_for_event_trb!(Doorbell {
+    db_reason,
+    vf_id,
+    slot_id
+});
 
 event!(
     HostController,
@@ -267,9 +485,14 @@ reserved!(DeviceNotification(Type::DeviceNotification){
 });
 impl DeviceNotification {
     /// Returns the value of the Notification Type field.
-    #[must_use]
-    pub fn notification_type(&self) -> u8 {
-        self.0[0].get_bits(4..=7).try_into().unwrap()
+    ///
+    /// # Errors
+    ///
+    /// This method may return an [`Err`] value with the Notification Type that is either reserved
+    /// or not implemented by this crate.
+    pub fn notification_type(&self) -> Result<DeviceNotificationType, u8> {
+        let t: u8 = self.0[0].get_bits(4..=7).try_into().unwrap();
+        DeviceNotificationType::from_u8(t).ok_or(t)
     }
 
     /// Returns the value of the Device Notification Data field.
@@ -302,6 +525,15 @@ reserved!(MfindexWrap(Type::MfindexWrap){
 });
 impl_debug_for_event_trb!(MfindexWrap {});
 
+typed_slot_id!(
+    TransferEvent,
+    CommandCompletion,
+    BandwidthRequest,
+    Doorbell,
+    DeviceNotification
+);
+typed_endpoint_id!(TransferEvent);
+
 /// The TRB Completion Codes.
 ///
 /// The description of each error is quoted from eXtensible Host Controller Interface for Universal
@@ -398,3 +630,30 @@ pub enum CompletionCode {
     /// Asserted if an error is detected on a USB2 protocol endpoint for a split transaction.
     SplitTransactionError = 36,
 }
+
+/// The Notification Types of the Device Notification Event TRB.
+///
+/// The values are defined in the Universal Serial Bus 3.2 Specification, Section 8.5.6.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, FromPrimitive)]
+pub enum DeviceNotificationType {
+    /// Function Wake.
+    FunctionWake = 1,
+    /// Latency Tolerance Message.
+    LatencyToleranceMessage = 2,
+    /// Bus Interval Adjustment Message.
+    BusIntervalAdjustmentMessage = 3,
+    /// Host Role Request.
+    HostRoleRequest = 4,
+    /// Sublink Speed.
+    SublinkSpeed = 5,
+}
+
+/// The decoded value of the Command Completion Parameter field of the Command Completion Event
+/// TRB.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum DecodedParameter {
+    /// The command does not use the field.
+    NotDefined,
+    /// The value of the Extended Property returned by the Get Extended Property Command.
+    ExtendedProperty(u32),
+}
@@ -1,7 +1,8 @@
 //! TRB (Transfer Request Block).
 
+use crate::{error, Error};
 use bit_field::BitField;
-use core::convert::TryInto;
+use core::convert::{TryFrom, TryInto};
 use num_derive::FromPrimitive;
 
 macro_rules! reserved{
@@ -9,19 +10,29 @@ macro_rules! reserved{
         $([$index:expr] $range:expr);*
     })=>{
         impl TryFrom<[u32;4]> for $name{
-            type Error=[u32;4];
+            type Error=crate::ring::trb::ConversionError;
 
             fn try_from(raw:[u32;4])->Result<Self,Self::Error>{
-                use crate::ring::trb::Type;
+                use crate::ring::trb::{ConversionError, Type};
+
+                let found:u8=raw[3].get_bits(10..=15).try_into().unwrap();
+                if found!=$ty as u8 {
+                    return Err(ConversionError::TypeMismatch{
+                        expected:$ty,
+                        found,
+                        raw,
+                    });
+                }
 
                 $(if raw[$index].get_bits($range) != 0{
-                    return Err(raw);
+                    return Err(ConversionError::ReservedBitsSet{
+                        dword:$index,
+                        first_bit:*$range.start(),
+                        last_bit:*$range.end(),
+                        raw,
+                    });
                 })*
 
-                if raw[3].get_bits(10..=15)!=$ty as _ {
-                    return Err(raw);
-                }
-
                 Ok(Self(raw))
             }
         }
@@ -111,6 +122,65 @@ macro_rules! impl_debug_for_trb{
     }
 }
 
+macro_rules! typed_slot_id {
+    ($($name:ident),*) => {
+        $(
+            impl $name {
+                /// Returns the value of the Slot ID field as a [`crate::ids::SlotId`].
+                ///
+                /// # Errors
+                ///
+                /// This method returns the raw value if it is 0.
+                pub fn slot(&self) -> Result<crate::ids::SlotId, u8> {
+                    core::convert::TryFrom::try_from(self.slot_id())
+                }
+            }
+        )*
+    };
+}
+macro_rules! typed_endpoint_id {
+    ($($name:ident),*) => {
+        $(
+            impl $name {
+                /// Returns the value of the Endpoint ID field as a [`crate::ids::EndpointId`].
+                ///
+                /// # Errors
+                ///
+                /// This method returns the raw value if it is not in the range of 1..=31.
+                pub fn endpoint(&self) -> Result<crate::ids::EndpointId, u8> {
+                    core::convert::TryFrom::try_from(self.endpoint_id())
+                }
+            }
+        )*
+    };
+}
+macro_rules! typed_slot_id_setter {
+    ($($name:ident),*) => {
+        typed_slot_id!($($name),*);
+        $(
+            impl $name {
+                /// Sets the value of the Slot ID field to `id`.
+                pub fn set_slot(&mut self, id: crate::ids::SlotId) -> &mut Self {
+                    self.set_slot_id(id.get())
+                }
+            }
+        )*
+    };
+}
+macro_rules! typed_endpoint_id_setter {
+    ($($name:ident),*) => {
+        typed_endpoint_id!($($name),*);
+        $(
+            impl $name {
+                /// Sets the value of the Endpoint ID field to `id`.
+                pub fn set_endpoint(&mut self, id: crate::ids::EndpointId) -> &mut Self {
+                    self.set_endpoint_id(id.get())
+                }
+            }
+        )*
+    };
+}
+
 macro_rules! allowed {
     (
         $(#[$outer:meta])*
@@ -152,6 +222,24 @@ macro_rules! allowed {
                 }
             }
         }
+        impl core::convert::TryFrom<[u32; 4]> for Allowed {
+            type Error = crate::ring::trb::ConversionError;
+
+            /// Converts the raw dwords into the TRB with the matching TRB Type.
+            fn try_from(raw: [u32; 4]) -> Result<Self, Self::Error> {
+                use crate::ring::trb::ConversionError;
+
+                $(match $variant::try_from(raw) {
+                    Err(ConversionError::TypeMismatch { .. }) => {}
+                    r => return r.map(Self::$variant),
+                })+
+
+                Err(ConversionError::UnexpectedType {
+                    found: raw[3].get_bits(10..=15).try_into().unwrap(),
+                    raw,
+                })
+            }
+        }
         impl AsRef<[u32]> for Allowed {
             fn as_ref(&self) -> &[u32]{
                 match self{
@@ -171,18 +259,85 @@ macro_rules! allowed {
 
 pub mod command;
 pub mod event;
+pub mod staging;
 pub mod transfer;
 
 /// The bytes of a TRB.
 pub const BYTES: usize = 16;
 
+/// The error returned when raw dwords cannot be converted into a TRB.
+///
+/// # Examples
+///
+/// ```
+/// use core::convert::TryFrom;
+/// use xhci::ring::trb::{event::CommandCompletion, ConversionError, Type};
+///
+/// // The TRB Type is Port Status Change Event.
+/// let r = CommandCompletion::try_from([0, 0, 0, 34 << 10]);
+///
+/// assert_eq!(
+///     r.unwrap_err(),
+///     ConversionError::TypeMismatch {
+///         expected: Type::CommandCompletion,
+///         found: 34,
+///         raw: [0, 0, 0, 34 << 10],
+///     }
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum ConversionError {
+    /// The TRB Type field is not the expected one.
+    TypeMismatch {
+        /// The expected TRB Type.
+        expected: Type,
+        /// The value of the TRB Type field.
+        found: u8,
+        /// The raw dwords.
+        raw: [u32; 4],
+    },
+    /// No TRB of the type is allowed.
+    UnexpectedType {
+        /// The value of the TRB Type field.
+        found: u8,
+        /// The raw dwords.
+        raw: [u32; 4],
+    },
+    /// Some of the reserved bits in `first_bit..=last_bit` of the dword `dword` are set.
+    ReservedBitsSet {
+        /// The index of the dword.
+        dword: usize,
+        /// The first bit of the reserved field.
+        first_bit: usize,
+        /// The last bit of the reserved field.
+        last_bit: usize,
+        /// The raw dwords.
+        raw: [u32; 4],
+    },
+}
+impl ConversionError {
+    /// Returns the raw dwords which failed to be converted.
+    #[must_use]
+    pub fn raw(self) -> [u32; 4] {
+        match self {
+            Self::TypeMismatch { raw, .. }
+            | Self::UnexpectedType { raw, .. }
+            | Self::ReservedBitsSet { raw, .. } => raw,
+        }
+    }
+}
+
 add_trb_with_default!(Link, "Link TRB", Type::Link);
+// The Link TRB is written by the software, so only the TRB Type is checked when converting the
+// raw dwords.
+reserved!(Link(Type::Link) {});
 impl Link {
     /// Sets the value of the Ring Segment Pointer field.
     ///
     /// # Panics
     ///
-    /// This method panics if `p` is not 16-byte aligned.
+    /// This method panics if `p` is not 16-byte aligned. Use
+    /// [`Link::try_set_ring_segment_pointer`] to handle the error.
     pub fn set_ring_segment_pointer(&mut self, p: u64) -> &mut Self {
         assert_eq!(
             p % 16,
@@ -198,6 +353,17 @@ impl Link {
         self
     }
 
+    /// Sets the value of the Ring Segment Pointer field without panicking.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::MisalignedPointer`] if `p` is not 16-byte aligned. The field
+    /// is not changed in this case.
+    pub fn try_set_ring_segment_pointer(&mut self, p: u64) -> Result<&mut Self, Error> {
+        error::check_alignment(p, 16)?;
+        Ok(self.set_ring_segment_pointer(p))
+    }
+
     /// Returns the value of the Ring Segment Pointer field.
     #[must_use]
     pub fn ring_segment_pointer(&self) -> u64 {
@@ -0,0 +1,142 @@
+//! Types to prepare TRBs in normal memory before copying them into a ring.
+//!
+//! # Examples
+//!
+//! ```
+//! use xhci::ring::trb::{
+//!     staging::{Buffer, Trb},
+//!     transfer,
+//! };
+//!
+//! let mut b = Buffer::<4>::new();
+//! let noop = transfer::Allowed::Noop(transfer::Noop::new());
+//! b.push(noop.into()).unwrap();
+//!
+//! # let mut ring = [Trb::default(); 4];
+//! // The destination must be 16-byte aligned and have enough room.
+//! unsafe { b.copy_to(ring.as_mut_ptr()) };
+//! ```
+
+use super::{command, event, transfer};
+use core::ptr;
+
+/// A TRB aligned on a 16-byte boundary.
+#[repr(C, align(16))]
+#[derive(Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Trb([u32; 4]);
+impl Trb {
+    /// Creates a TRB from the raw dwords.
+    #[must_use]
+    pub const fn new(raw: [u32; 4]) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the wrapped array.
+    #[must_use]
+    pub const fn into_raw(self) -> [u32; 4] {
+        self.0
+    }
+
+    /// Writes this TRB to `dst` with a single volatile write.
+    ///
+    /// Note that the compiler may split the write into smaller ones on some targets. If the order
+    /// of the writes matters, write the TRB with the Cycle Bit which does not pass the ownership to
+    /// the xHC, and then flip the bit.
+    ///
+    /// # Safety
+    ///
+    /// `dst` must be valid for writes and aligned on a 16-byte boundary.
+    pub unsafe fn write_volatile(self, dst: *mut Self) {
+        ptr::write_volatile(dst, self);
+    }
+}
+impl From<command::Allowed> for Trb {
+    fn from(t: command::Allowed) -> Self {
+        Self(t.into_raw())
+    }
+}
+impl From<event::Allowed> for Trb {
+    fn from(t: event::Allowed) -> Self {
+        Self(t.into_raw())
+    }
+}
+impl From<transfer::Allowed> for Trb {
+    fn from(t: transfer::Allowed) -> Self {
+        transfer::assert_data_buffer_within_boundary(&t);
+        Self(t.into_raw())
+    }
+}
+impl AsRef<[u32]> for Trb {
+    fn as_ref(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+/// A fixed-size buffer to stage up to `N` TRBs.
+#[derive(Copy, Clone, Debug)]
+pub struct Buffer<const N: usize> {
+    trbs: [Trb; N],
+    len: usize,
+}
+impl<const N: usize> Buffer<N> {
+    /// Creates an empty buffer.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            trbs: [Trb::new([0; 4]); N],
+            len: 0,
+        }
+    }
+
+    /// Appends a TRB to the buffer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns `trb` back if the buffer is full.
+    pub fn push(&mut self, trb: Trb) -> Result<(), Trb> {
+        let slot = self.trbs.get_mut(self.len).ok_or(trb)?;
+        *slot = trb;
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Returns the staged TRBs.
+    #[must_use]
+    pub fn as_slice(&self) -> &[Trb] {
+        &self.trbs[..self.len]
+    }
+
+    /// Returns the number of the staged TRBs.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no TRB is staged.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all the staged TRBs.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Copies the staged TRBs to `dst` in order, with one volatile write per TRB.
+    ///
+    /// # Safety
+    ///
+    /// `dst` must be aligned on a 16-byte boundary and valid for writes of [`Buffer::len`] TRBs.
+    pub unsafe fn copy_to(&self, dst: *mut Trb) {
+        for (i, t) in self.as_slice().iter().enumerate() {
+            t.write_volatile(dst.add(i));
+        }
+    }
+}
+impl<const N: usize> Default for Buffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
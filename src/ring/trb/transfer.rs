@@ -2,7 +2,7 @@
 
 use super::Link;
 use bit_field::BitField;
-use core::convert::TryInto;
+use core::convert::{TryFrom, TryInto};
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 
@@ -27,6 +27,16 @@ allowed! {
         Noop
     }
 }
+
+// The Transfer TRBs are written by the software, so only the TRB Type is checked when converting
+// the raw dwords.
+reserved!(Normal(Type::Normal) {});
+reserved!(SetupStage(Type::SetupStage) {});
+reserved!(DataStage(Type::DataStage) {});
+reserved!(StatusStage(Type::StatusStage) {});
+reserved!(Isoch(Type::Isoch) {});
+reserved!(EventData(Type::EventData) {});
+reserved!(Noop(Type::NoopTransfer) {});
 impl Allowed {
     /// Sets the value of the Interrupt On Completion field.
     // Unavoidable because the match arms has to be the same return types.
@@ -139,7 +149,7 @@ impl Normal {
         let l: u64 = self.0[0].into();
         let u: u64 = self.0[1].into();
 
-        (l << 32) | u
+        (u << 32) | l
     }
 
     /// Sets the value of the TRB Transfer Length field.
@@ -153,10 +163,23 @@ impl Normal {
     pub fn trb_transfer_length(&self) -> u32 {
         self.0[2].get_bits(0..=16)
     }
+
+    /// Sets the value of the Chain Bit field.
+    pub fn set_chain_bit(&mut self, b: bool) -> &mut Self {
+        self.0[3].set_bit(4, b);
+        self
+    }
+
+    /// Returns the value of the Chain Bit field.
+    #[must_use]
+    pub fn chain_bit(&self) -> bool {
+        self.0[3].get_bit(4)
+    }
 }
 impl_debug_for_transfer_trb!(Normal {
     data_buffer_pointer,
-    trb_transfer_length
+    trb_transfer_length,
+    chain_bit
 });
 
 transfer_trb!(SetupStage, "Setup Stage TRB", Type::SetupStage);
@@ -671,3 +694,19 @@ pub enum TransferType {
     /// In Data Stage.
     In = 3,
 }
+
+// Checks the requirement of the xHCI specification Section 6.4.1 that the data buffer of a TRB
+// must not cross a 64 KB boundary. The pointer field holds the data itself if the Immediate Data
+// bit is set.
+//
+// This function must be called on a complete TRB because the result depends on both the Data
+// Buffer Pointer and the TRB Transfer Length.
+pub(crate) fn assert_data_buffer_within_boundary(t: &Allowed) {
+    if let Allowed::Normal(_) | Allowed::DataStage(_) | Allowed::Isoch(_) = t {
+        let trb = t.as_ref();
+        strict_assert!(
+            trb[3].get_bit(6) || (trb[0] & 0xffff) + trb[2].get_bits(0..=16) <= 0x1_0000,
+            "The data buffer must not cross a 64 KB boundary."
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! The constants of the standard USB device requests.
+//!
+//! These values are defined in the Universal Serial Bus 3.2 Specification, Section 9.4. Use them to
+//! fill the fields of the Setup Stage TRB.
+//!
+//! # Examples
+//!
+//! ```
+//! use xhci::{
+//!     ring::trb::transfer::{SetupStage, TransferType},
+//!     usb_req::{descriptor_type, request, request_type},
+//! };
+//!
+//! let mut setup = SetupStage::new();
+//! setup
+//!     .set_request_type(
+//!         request_type::DIRECTION_IN | request_type::TYPE_STANDARD | request_type::RECIPIENT_DEVICE,
+//!     )
+//!     .set_request(request::GET_DESCRIPTOR)
+//!     .set_value(u16::from(descriptor_type::DEVICE) << 8)
+//!     .set_length(18)
+//!     .set_trb_transfer_length(8)
+//!     .set_transfer_type(TransferType::In);
+//! ```
+
+pub mod request_type {
+    //! The bits of the `bmRequestType` field.
+
+    /// Device-to-host.
+    pub const DIRECTION_IN: u8 = 0x80;
+    /// Host-to-device.
+    pub const DIRECTION_OUT: u8 = 0x00;
+
+    /// Standard request.
+    pub const TYPE_STANDARD: u8 = 0x00;
+    /// Class request.
+    pub const TYPE_CLASS: u8 = 0x20;
+    /// Vendor request.
+    pub const TYPE_VENDOR: u8 = 0x40;
+
+    /// The recipient is the device.
+    pub const RECIPIENT_DEVICE: u8 = 0x00;
+    /// The recipient is an interface.
+    pub const RECIPIENT_INTERFACE: u8 = 0x01;
+    /// The recipient is an endpoint.
+    pub const RECIPIENT_ENDPOINT: u8 = 0x02;
+    /// The recipient is other than the above.
+    pub const RECIPIENT_OTHER: u8 = 0x03;
+}
+
+pub mod request {
+    //! The standard request codes for the `bRequest` field.
+
+    /// `GET_STATUS`
+    pub const GET_STATUS: u8 = 0;
+    /// `CLEAR_FEATURE`
+    pub const CLEAR_FEATURE: u8 = 1;
+    /// `SET_FEATURE`
+    pub const SET_FEATURE: u8 = 3;
+    /// `SET_ADDRESS`
+    pub const SET_ADDRESS: u8 = 5;
+    /// `GET_DESCRIPTOR`
+    pub const GET_DESCRIPTOR: u8 = 6;
+    /// `SET_DESCRIPTOR`
+    pub const SET_DESCRIPTOR: u8 = 7;
+    /// `GET_CONFIGURATION`
+    pub const GET_CONFIGURATION: u8 = 8;
+    /// `SET_CONFIGURATION`
+    pub const SET_CONFIGURATION: u8 = 9;
+    /// `GET_INTERFACE`
+    pub const GET_INTERFACE: u8 = 10;
+    /// `SET_INTERFACE`
+    pub const SET_INTERFACE: u8 = 11;
+    /// `SYNCH_FRAME`
+    pub const SYNCH_FRAME: u8 = 12;
+    /// `SET_SEL`
+    pub const SET_SEL: u8 = 48;
+    /// `SET_ISOCH_DELAY`
+    pub const SET_ISOCH_DELAY: u8 = 49;
+}
+
+pub mod descriptor_type {
+    //! The descriptor types.
+    //!
+    //! For the `GET_DESCRIPTOR` request, the descriptor type goes to the upper byte of the `wValue`
+    //! field.
+
+    /// Device descriptor.
+    pub const DEVICE: u8 = 1;
+    /// Configuration descriptor.
+    pub const CONFIGURATION: u8 = 2;
+    /// String descriptor.
+    pub const STRING: u8 = 3;
+    /// Interface descriptor.
+    pub const INTERFACE: u8 = 4;
+    /// Endpoint descriptor.
+    pub const ENDPOINT: u8 = 5;
+    /// Interface Power descriptor.
+    pub const INTERFACE_POWER: u8 = 8;
+    /// OTG descriptor.
+    pub const OTG: u8 = 9;
+    /// Debug descriptor.
+    pub const DEBUG: u8 = 10;
+    /// Interface Association descriptor.
+    pub const INTERFACE_ASSOCIATION: u8 = 11;
+    /// BOS descriptor.
+    pub const BOS: u8 = 15;
+    /// Device Capability descriptor.
+    pub const DEVICE_CAPABILITY: u8 = 16;
+    /// `SuperSpeed` Endpoint Companion descriptor.
+    pub const SUPERSPEED_USB_ENDPOINT_COMPANION: u8 = 48;
+    /// `SuperSpeedPlus` Isochronous Endpoint Companion descriptor.
+    pub const SUPERSPEEDPLUS_ISOCHRONOUS_ENDPOINT_COMPANION: u8 = 49;
+}
+
+pub mod feature_selector {
+    //! The standard feature selectors for the `CLEAR_FEATURE` and `SET_FEATURE` requests.
+
+    /// `ENDPOINT_HALT`, for an endpoint.
+    pub const ENDPOINT_HALT: u16 = 0;
+    /// `FUNCTION_SUSPEND`, for an interface.
+    pub const FUNCTION_SUSPEND: u16 = 0;
+    /// `DEVICE_REMOTE_WAKEUP`, for a device.
+    pub const DEVICE_REMOTE_WAKEUP: u16 = 1;
+    /// `TEST_MODE`, for a device.
+    pub const TEST_MODE: u16 = 2;
+    /// `U1_ENABLE`, for a device.
+    pub const U1_ENABLE: u16 = 48;
+    /// `U2_ENABLE`, for a device.
+    pub const U2_ENABLE: u16 = 49;
+    /// `LTM_ENABLE`, for a device.
+    pub const LTM_ENABLE: u16 = 50;
+}